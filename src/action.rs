@@ -0,0 +1,32 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    Quit,
+    Skip,
+    PrintRules,
+    Undo,
+    Redo,
+    Kill { index: usize },
+    Heal { index: usize },
+    Fight { index: usize },
+    Equip { index: usize },
+}
+
+impl Action {
+    // The short form used in replays, e.g. `s` or `a2`. Meta actions have none.
+    pub fn notation(&self) -> Option<String> {
+        match self {
+            Action::Skip => Some("s".to_string()),
+            Action::Fight { index } => Some(format!("f{}", index + 1)),
+            Action::Kill { index } => Some(format!("a{}", index + 1)),
+            Action::Equip { index } => Some(format!("e{}", index + 1)),
+            Action::Heal { index } => Some(format!("h{}", index + 1)),
+            Action::Quit | Action::PrintRules | Action::Undo | Action::Redo => None,
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use std::{collections::BTreeMap, fs, path::PathBuf, time::Duration};
+
+use scoundrel::{
+    action::Action,
+    deck::Deck,
+    game::{Ending, Game},
+    ismcts::{Budget, Ismcts},
+    rules::RuleSet,
+    strategy::{self, Greedy, RandomLegal, Strategy, WeaponKeeper},
+};
+
+const STRATEGIES: &str = "greedy, keeper, random, ismcts";
+
+struct Options {
+    strategy: String,
+    games: u64,
+    first_seed: u64,
+    rules_path: Option<PathBuf>,
+    deck_path: Option<PathBuf>,
+    budget: Budget,
+    csv_path: Option<PathBuf>,
+    json_path: Option<PathBuf>,
+}
+
+#[derive(serde::Serialize)]
+struct Record {
+    seed: u64,
+    ending: &'static str,
+    score: i16,
+    turns: u8,
+    skips: usize,
+}
+
+fn main() {
+    let options = parse_args();
+
+    let rules = match options.rules_path.as_deref().map(RuleSet::load) {
+        None => RuleSet::official(),
+        Some(Ok(rules)) => rules,
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let spec = match &options.deck_path {
+        Some(path) => match fs::read_to_string(path) {
+            Ok(spec) => Some(spec),
+            Err(e) => {
+                eprintln!("Could not read deck: {e}");
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let Some(end) = options.first_seed.checked_add(options.games) else {
+        eprintln!("--seed plus --games must be at most {}", u64::MAX);
+        std::process::exit(1);
+    };
+
+    let mut records = Vec::new();
+
+    for seed in options.first_seed..end {
+        let Some(mut strategy) = pick_strategy(&options.strategy, seed, options.budget) else {
+            eprintln!(
+                "Unknown strategy \"{}\", pick one of: {}",
+                options.strategy, STRATEGIES
+            );
+            std::process::exit(1);
+        };
+
+        let deck = match &spec {
+            Some(spec) => Deck::from_spec(spec, seed).map_err(|e| e.to_string()),
+            None => Ok(Deck::with_seed(seed)),
+        };
+
+        let record = deck
+            .and_then(|deck| Game::from_deck(deck, rules).map_err(|e| e.to_string()))
+            .and_then(|game| play(game, seed, &mut strategy));
+
+        match record {
+            Ok(record) => records.push(record),
+            Err(e) => {
+                eprintln!("Seed {}: {}", seed, e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    print_summary(&options, &records);
+
+    if let Some(path) = &options.csv_path
+        && let Err(e) = fs::write(path, to_csv(&records))
+    {
+        eprintln!("Could not write {}: {}", path.display(), e);
+    }
+
+    if let Some(path) = &options.json_path {
+        let written = serde_json::to_string_pretty(&records)
+            .map_err(|e| e.to_string())
+            .and_then(|json| fs::write(path, json).map_err(|e| e.to_string()));
+
+        if let Err(e) = written {
+            eprintln!("Could not write {}: {}", path.display(), e);
+        }
+    }
+}
+
+fn pick_strategy(name: &str, seed: u64, budget: Budget) -> Option<Box<dyn Strategy>> {
+    match name {
+        "greedy" => Some(Box::new(Greedy)),
+        "keeper" => Some(Box::new(WeaponKeeper)),
+        "random" => Some(Box::new(RandomLegal::new(seed))),
+        "ismcts" => Some(Box::new(Ismcts::new(budget, seed))),
+        _ => None,
+    }
+}
+
+fn play(mut game: Game, seed: u64, strategy: &mut Box<dyn Strategy>) -> Result<Record, String> {
+    let outcome = strategy::play(&mut game, strategy).map_err(|e| e.to_string())?;
+
+    let ending = match outcome.ending {
+        Ending::ClearedDungeon => "cleared",
+        Ending::LeftLastRoom => "left",
+        Ending::Died => "died",
+    };
+
+    return Ok(Record {
+        seed,
+        ending,
+        score: outcome.score,
+        turns: game.game_info().turn,
+        skips: game
+            .actions()
+            .iter()
+            .filter(|action| **action == Action::Skip)
+            .count(),
+    });
+}
+
+fn print_summary(options: &Options, records: &[Record]) {
+    let games = records.len().max(1) as f64;
+    let wins = records.iter().filter(|r| r.ending != "died").count();
+    let deaths: Vec<&Record> = records.iter().filter(|r| r.ending == "died").collect();
+
+    let mut scores: Vec<i16> = records.iter().map(|r| r.score).collect();
+    scores.sort();
+
+    println!("Strategy:           {}", options.strategy);
+    println!(
+        "Seeds:              {} to {}",
+        options.first_seed,
+        options.first_seed + options.games.saturating_sub(1)
+    );
+    println!("Games:              {}", records.len());
+    println!(
+        "Wins:               {} ({:.1}%)",
+        wins,
+        wins as f64 * 100.0 / games
+    );
+    println!(
+        "Average score:      {}",
+        average(scores.iter().map(|s| *s as f64))
+    );
+
+    if let (Some(lowest), Some(highest)) = (scores.first(), scores.last()) {
+        println!("Median score:       {}", scores[scores.len() / 2]);
+        println!("Score range:        {} to {}", lowest, highest);
+    }
+
+    println!(
+        "Average death turn: {}",
+        average(deaths.iter().map(|r| r.turns as f64))
+    );
+    println!(
+        "Skips per game:     {}",
+        average(records.iter().map(|r| r.skips as f64))
+    );
+    println!(
+        "Games with a skip:  {:.1}%",
+        records.iter().filter(|r| r.skips > 0).count() as f64 * 100.0 / games
+    );
+
+    // Scores grouped by tens, e.g. 20 to 29.
+    let mut buckets: BTreeMap<i16, usize> = BTreeMap::new();
+    for score in &scores {
+        *buckets.entry(score.div_euclid(10) * 10).or_insert(0) += 1;
+    }
+
+    let most = buckets.values().copied().max().unwrap_or(1);
+
+    println!();
+    println!("  Score        Games");
+
+    for (bucket, count) in buckets {
+        println!(
+            "  {:>4} to {:>4} {:>5}  {}",
+            bucket,
+            bucket + 9,
+            count,
+            "#".repeat((count * 40).div_ceil(most))
+        );
+    }
+}
+
+// `-` when there is nothing to average, e.g. the death turn of a perfect run.
+fn average(values: impl Iterator<Item = f64>) -> String {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+
+    if count == 0 {
+        return "-".to_string();
+    }
+
+    return format!("{:.1}", sum / count as f64);
+}
+
+fn to_csv(records: &[Record]) -> String {
+    let mut csv = String::from("seed,ending,score,turns,skips\n");
+
+    for r in records {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            r.seed, r.ending, r.score, r.turns, r.skips
+        ));
+    }
+
+    return csv;
+}
+
+fn parse_args() -> Options {
+    let mut options = Options {
+        strategy: "greedy".to_string(),
+        games: 100,
+        first_seed: 0,
+        rules_path: None,
+        deck_path: None,
+        budget: Budget::Iterations(1000),
+        csv_path: None,
+        json_path: None,
+    };
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--strategy" => {
+                if let Some(name) = args.next() {
+                    options.strategy = name;
+                }
+            }
+            "--games" => options.games = args.next().and_then(|v| v.parse().ok()).unwrap_or(100),
+            "--seed" => options.first_seed = args.next().and_then(|v| v.parse().ok()).unwrap_or(0),
+            "--rules" => options.rules_path = args.next().map(PathBuf::from),
+            "--deck" => options.deck_path = args.next().map(PathBuf::from),
+            "--iterations" => {
+                if let Some(iterations) = args.next().and_then(|v| v.parse().ok()) {
+                    options.budget = Budget::Iterations(iterations);
+                }
+            }
+            "--time-ms" => {
+                if let Some(millis) = args.next().and_then(|v| v.parse().ok()) {
+                    options.budget = Budget::Time(Duration::from_millis(millis));
+                }
+            }
+            "--csv" => options.csv_path = args.next().map(PathBuf::from),
+            "--json" => options.json_path = args.next().map(PathBuf::from),
+            _ => {}
+        }
+    }
+
+    options
+}
@@ -1,4 +1,10 @@
-#[derive(Debug, Clone, Copy)]
+use alloc::string::ToString;
+use core::{fmt, str::FromStr};
+
+use crate::error::CardParseError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suit {
     Spades,
     Hearts,
@@ -6,7 +12,8 @@ pub enum Suit {
     Diamonds,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rank {
     Num(u8),
     Jack,
@@ -15,14 +22,16 @@ pub enum Rank {
     Ace,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CardKind {
     Monster,
     Weapon,
     Potion,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Card {
     pub suit: Suit,
     pub rank: Rank,
@@ -30,7 +39,37 @@ pub struct Card {
     pub kind: CardKind,
 }
 
+impl Rank {
+    pub fn from_strength(strength: u8) -> Result<Self, CardParseError> {
+        match strength {
+            2..=10 => Ok(Rank::Num(strength)),
+            11 => Ok(Rank::Jack),
+            12 => Ok(Rank::Queen),
+            13 => Ok(Rank::King),
+            14 => Ok(Rank::Ace),
+            other => Err(CardParseError::InvalidRank(other)),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Rank::Num(value) => (2..=10).contains(value),
+            _ => true,
+        }
+    }
+}
+
 impl Card {
+    pub fn try_new(suit: Suit, rank: Rank) -> Result<Self, CardParseError> {
+        if let Rank::Num(value) = rank
+            && !rank.is_valid()
+        {
+            return Err(CardParseError::InvalidRank(value));
+        }
+
+        return Ok(Self::new(suit, rank));
+    }
+
     pub fn new(suit: Suit, rank: Rank) -> Self {
         let strength = match rank {
             Rank::Jack => 11,
@@ -54,3 +93,96 @@ impl Card {
         };
     }
 }
+
+impl FromStr for Suit {
+    type Err = CardParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "♠" | "s" | "spades" => Ok(Suit::Spades),
+            "♣" | "c" | "clubs" => Ok(Suit::Clubs),
+            "♦" | "d" | "diamonds" => Ok(Suit::Diamonds),
+            "♥" | "h" | "hearts" => Ok(Suit::Hearts),
+            other => Err(CardParseError::UnknownSuit(other.to_string())),
+        }
+    }
+}
+
+impl FromStr for Rank {
+    type Err = CardParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "J" => Ok(Rank::Jack),
+            "Q" => Ok(Rank::Queen),
+            "K" => Ok(Rank::King),
+            "A" => Ok(Rank::Ace),
+            "T" => Ok(Rank::Num(10)),
+            number => match number.parse::<u8>() {
+                Ok(value) if (2..=10).contains(&value) => Ok(Rank::Num(value)),
+                _ => Err(CardParseError::UnknownRank(number.to_string())),
+            },
+        }
+    }
+}
+
+// Accepts the suit on either side of the rank, as a symbol or a letter:
+// "♠K", "SK", "KS" and "10h" are all valid.
+impl FromStr for Card {
+    type Err = CardParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let malformed = || CardParseError::Malformed(s.to_string());
+
+        let first = s.chars().next().ok_or_else(malformed)?;
+        let last = s.chars().last().ok_or_else(malformed)?;
+
+        if let Ok(suit) = first.to_string().parse::<Suit>()
+            && let Ok(rank) = s[first.len_utf8()..].parse::<Rank>()
+        {
+            return Ok(Card::new(suit, rank));
+        }
+
+        if let Ok(suit) = last.to_string().parse::<Suit>()
+            && let Ok(rank) = s[..s.len() - last.len_utf8()].parse::<Rank>()
+        {
+            return Ok(Card::new(suit, rank));
+        }
+
+        Err(malformed())
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Suit::Spades => "♠",
+            Suit::Clubs => "♣",
+            Suit::Diamonds => "♦",
+            Suit::Hearts => "♥",
+        };
+
+        write!(f, "{msg}")
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Ace => "A",
+            Rank::Num(v) => &v.to_string(),
+        };
+
+        write!(f, "{msg}")
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.suit, self.rank)
+    }
+}
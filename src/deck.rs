@@ -1,14 +1,245 @@
-use rand::{seq::SliceRandom, thread_rng};
+#[cfg(feature = "rand-seeded")]
+use alloc::string::ToString;
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
 
-use crate::card::{Card, CardKind, Rank, Suit};
+#[cfg(feature = "rand-seeded")]
+use rand::{RngCore, SeedableRng};
+#[cfg(feature = "rand-seeded")]
+use rand_chacha::ChaCha8Rng;
 
-#[derive(Debug)]
+use crate::{
+    card::{Card, CardKind},
+    error::DealError,
+};
+#[cfg(feature = "rand-seeded")]
+use crate::{
+    card::{Rank, Suit},
+    error::DeckSpecError,
+};
+
+// The most cards a deck may hold. Even if every other turn is a skip, a game
+// then stays within the `u8` turn counter and its score within an `i16`.
+pub const MAX_DECK_SIZE: usize = 120;
+
+#[cfg(feature = "rand-seeded")]
+const RANKS: [Rank; 13] = [
+    Rank::Num(2),
+    Rank::Num(3),
+    Rank::Num(4),
+    Rank::Num(5),
+    Rank::Num(6),
+    Rank::Num(7),
+    Rank::Num(8),
+    Rank::Num(9),
+    Rank::Num(10),
+    Rank::Jack,
+    Rank::Queen,
+    Rank::King,
+    Rank::Ace,
+];
+
+// Remaining card counts keyed by strength.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Composition {
+    pub monsters: BTreeMap<u8, usize>,
+    pub weapons: BTreeMap<u8, usize>,
+    pub potions: BTreeMap<u8, usize>,
+}
+
+impl Composition {
+    pub fn from_cards<'a>(cards: impl IntoIterator<Item = &'a Card>) -> Self {
+        let mut composition = Self::default();
+
+        for card in cards {
+            *composition
+                .counts_mut(card.kind)
+                .entry(card.strength)
+                .or_insert(0) += 1;
+        }
+
+        return composition;
+    }
+
+    // Removes the given cards, e.g. the known bottom cards to get what is
+    // left to draw from before reaching them.
+    pub fn excluding<'a>(&self, cards: impl IntoIterator<Item = &'a Card>) -> Self {
+        let mut composition = self.clone();
+
+        for card in cards {
+            let counts = composition.counts_mut(card.kind);
+
+            if let Some(count) = counts.get_mut(&card.strength) {
+                *count -= 1;
+
+                if *count == 0 {
+                    counts.remove(&card.strength);
+                }
+            }
+        }
+
+        return composition;
+    }
+
+    pub fn counts(&self, kind: CardKind) -> &BTreeMap<u8, usize> {
+        match kind {
+            CardKind::Monster => &self.monsters,
+            CardKind::Weapon => &self.weapons,
+            CardKind::Potion => &self.potions,
+        }
+    }
+
+    pub fn count(&self, kind: CardKind) -> usize {
+        return self.counts(kind).values().sum();
+    }
+
+    pub fn total_strength(&self, kind: CardKind) -> u16 {
+        return self
+            .counts(kind)
+            .iter()
+            .map(|(strength, count)| *strength as u16 * *count as u16)
+            .sum();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.count(CardKind::Monster)
+            + self.count(CardKind::Weapon)
+            + self.count(CardKind::Potion);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    fn counts_mut(&mut self, kind: CardKind) -> &mut BTreeMap<u8, usize> {
+        match kind {
+            CardKind::Monster => &mut self.monsters,
+            CardKind::Weapon => &mut self.weapons,
+            CardKind::Potion => &mut self.potions,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Deck {
     cards: Vec<Card>,
+    seed: Option<u64>,
+    spec: Option<String>,
+    dealt: Option<Vec<Card>>,
+    known_bottom: usize,
+}
+
+impl Deck {
+    // An unshuffled deck dealt in the given order, the first card is drawn first.
+    pub fn from_cards(cards: Vec<Card>) -> Result<Self, DealError> {
+        if cards.len() > MAX_DECK_SIZE {
+            return Err(DealError::TooManyCards(cards.len()));
+        }
+
+        return Ok(Self {
+            cards: cards.iter().rev().cloned().collect(),
+            seed: None,
+            spec: None,
+            dealt: Some(cards),
+            known_bottom: 0,
+        });
+    }
+
+    // `None` for decks built from an explicit card order.
+    pub fn seed(&self) -> Option<u64> {
+        return self.seed;
+    }
+
+    // The normalized spec this deck was built from, `None` for the standard deck.
+    pub fn spec(&self) -> Option<&str> {
+        return self.spec.as_deref();
+    }
+
+    // The card order this deck was built from, `None` for shuffled decks.
+    pub fn dealt(&self) -> Option<&[Card]> {
+        return self.dealt.as_deref();
+    }
+
+    pub fn draw(&mut self) -> Option<Card> {
+        let card = self.cards.pop();
+        self.known_bottom = self.known_bottom.min(self.cards.len());
+
+        card
+    }
+
+    // Bottom card first, like `known_bottom`.
+    pub fn cards(&self) -> &[Card] {
+        return &self.cards;
+    }
+
+    pub fn cards_mut(&mut self) -> &mut Vec<Card> {
+        return &mut self.cards;
+    }
+
+    pub fn put_on_bottom(&mut self, card: Card) {
+        self.cards.insert(0, card);
+        self.known_bottom += 1;
+    }
+
+    // Keeps the given order, so the first card ends up at the very bottom.
+    pub fn put_all_on_bottom(&mut self, cards: Vec<Card>) {
+        self.known_bottom += cards.len();
+        self.cards.splice(0..0, cards);
+    }
+
+    // Cards that were seen before being put back, bottom card first.
+    pub fn known_bottom(&self) -> &[Card] {
+        return &self.cards[..self.known_bottom.min(self.cards.len())];
+    }
+
+    // The remaining cards in the order they will be drawn.
+    pub fn draw_order(&self) -> Vec<Card> {
+        return self.cards.iter().rev().cloned().collect();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.cards.is_empty();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.cards.len();
+    }
+
+    pub fn composition(&self) -> Composition {
+        return Composition::from_cards(&self.cards);
+    }
+
+    // What the unseen part of the deck, above the known bottom cards, is made of.
+    pub fn unknown_composition(&self) -> Composition {
+        return self.composition().excluding(self.known_bottom());
+    }
+
+    pub fn remaining_monster_strength(&self) -> u16 {
+        return self
+            .cards
+            .iter()
+            .filter(|card| matches!(card.kind, CardKind::Monster))
+            .map(|card| card.strength as u16)
+            .sum();
+    }
 }
 
+#[cfg(feature = "rand-seeded")]
 impl Deck {
+    #[cfg(feature = "std")]
     pub fn new() -> Self {
+        return Self::from_rng(&mut rand::thread_rng());
+    }
+
+    // Takes the seed from the given generator, so platforms without
+    // `thread_rng` can bring their own entropy and still get a replayable seed.
+    pub fn from_rng(rng: &mut impl RngCore) -> Self {
+        return Self::with_seed(rng.next_u64());
+    }
+
+    // ChaCha8 and the shuffle below are both fixed algorithms, so a seed
+    // deals the same dungeon across releases.
+    pub fn with_seed(seed: u64) -> Self {
         let mut cards: Vec<Card> = Vec::new();
 
         for suit in [Suit::Spades, Suit::Clubs] {
@@ -47,39 +278,163 @@ impl Deck {
             }
         }
 
-        let mut rng = thread_rng();
+        return Self::shuffled(cards, seed, None);
+    }
+
+    // A spec lists one suit per line (or per `;`), followed by its ranks and
+    // an optional count, e.g. `hearts 2-10,J,Q,K x2`. `#` starts a comment.
+    pub fn from_spec(spec: &str, seed: u64) -> Result<Self, DeckSpecError> {
+        let mut cards = Vec::new();
+        let mut entries = Vec::new();
+
+        for (index, line) in spec.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or("");
+
+            for entry in line.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+                cards.extend(Self::parse_spec_entry(entry, line_number, cards.len())?);
+                entries.push(entry.split_whitespace().collect::<Vec<_>>().join(" "));
+            }
+        }
 
-        cards.shuffle(&mut rng);
+        if cards.is_empty() {
+            return Err(DeckSpecError::EmptyDeck);
+        }
 
-        return Self { cards };
+        return Ok(Self::shuffled(cards, seed, Some(entries.join("; "))));
     }
 
-    pub fn draw(&mut self) -> Option<Card> {
-        self.cards.pop()
+    fn shuffled(mut cards: Vec<Card>, seed: u64, spec: Option<String>) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        Self::shuffle(&mut cards, &mut rng);
+
+        return Self {
+            cards,
+            seed: Some(seed),
+            spec,
+            dealt: None,
+            known_bottom: 0,
+        };
     }
 
-    pub fn cards_mut(&mut self) -> &mut Vec<Card> {
-        return &mut self.cards;
+    // `total` is the number of cards read before this entry, the deck size
+    // is checked before any card is built.
+    fn parse_spec_entry(
+        entry: &str,
+        line: usize,
+        total: usize,
+    ) -> Result<Vec<Card>, DeckSpecError> {
+        let mut parts = entry.split_whitespace();
+
+        let raw_suit = parts.next().unwrap_or_default();
+        let suit = raw_suit
+            .parse::<Suit>()
+            .map_err(|_| DeckSpecError::UnknownSuit {
+                line,
+                suit: raw_suit.to_string(),
+            })?;
+
+        let ranks = parts.next().ok_or(DeckSpecError::MissingRanks { line })?;
+
+        let count = match parts.next() {
+            None => 1,
+            Some(raw) => match raw.strip_prefix('x').and_then(|n| n.parse::<usize>().ok()) {
+                Some(count) if count > 0 => count,
+                _ => {
+                    return Err(DeckSpecError::InvalidCount {
+                        line,
+                        count: raw.to_string(),
+                    });
+                }
+            },
+        };
+
+        if let Some(extra) = parts.next() {
+            return Err(DeckSpecError::UnexpectedToken {
+                line,
+                token: extra.to_string(),
+            });
+        }
+
+        let mut groups = Vec::new();
+
+        for group in ranks.split(',') {
+            let (first, last) = group.split_once('-').unwrap_or((group, group));
+            let start = Self::parse_spec_rank(first, line)?;
+            let end = Self::parse_spec_rank(last, line)?;
+
+            if start > end {
+                return Err(DeckSpecError::UnsupportedRank {
+                    line,
+                    rank: group.to_string(),
+                });
+            }
+
+            groups.push(start..=end);
+        }
+
+        let ranks: usize = groups.iter().map(|group| group.clone().count()).sum();
+        let size = ranks
+            .checked_mul(count)
+            .and_then(|cards| cards.checked_add(total))
+            .unwrap_or(usize::MAX);
+
+        if size > MAX_DECK_SIZE {
+            return Err(DeckSpecError::TooManyCards(size));
+        }
+
+        let mut cards = Vec::with_capacity(size - total);
+
+        for group in groups {
+            for rank in &RANKS[group] {
+                for _ in 0..count {
+                    cards.push(Card::new(suit, *rank));
+                }
+            }
+        }
+
+        return Ok(cards);
     }
 
-    pub fn put_on_bottom(&mut self, card: Card) {
-        self.cards.insert(0, card);
+    // Returns the position of the rank in RANKS.
+    fn parse_spec_rank(raw: &str, line: usize) -> Result<usize, DeckSpecError> {
+        let unsupported = || DeckSpecError::UnsupportedRank {
+            line,
+            rank: raw.to_string(),
+        };
+
+        let rank = raw.parse::<Rank>().map_err(|_| unsupported())?;
+
+        return RANKS
+            .iter()
+            .position(|r| *r == rank)
+            .ok_or_else(unsupported);
     }
 
-    pub fn is_empty(&self) -> bool {
-        return self.cards.is_empty();
+    fn shuffle(cards: &mut [Card], rng: &mut ChaCha8Rng) {
+        for i in (1..cards.len()).rev() {
+            let j = Self::uniform_below(rng, i as u32 + 1) as usize;
+            cards.swap(i, j);
+        }
     }
 
-    pub fn len(&self) -> usize {
-        return self.cards.len();
+    fn uniform_below(rng: &mut ChaCha8Rng, bound: u32) -> u32 {
+        let zone = u32::MAX - u32::MAX % bound;
+
+        loop {
+            let value = rng.next_u32();
+
+            if value < zone {
+                return value % bound;
+            }
+        }
     }
+}
 
-    pub fn remaining_monster_strength(&self) -> u8 {
-        return self
-            .cards
-            .iter()
-            .filter(|card| matches!(card.kind, CardKind::Monster))
-            .map(|card| card.strength)
-            .sum();
+#[cfg(all(feature = "rand-seeded", feature = "std"))]
+impl Default for Deck {
+    fn default() -> Self {
+        return Self::new();
     }
 }
@@ -0,0 +1,62 @@
+use alloc::vec::Vec;
+
+use crate::card::Card;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Resolution {
+    Fought,
+    Slain,
+    PotionDrunk,
+    PotionWasted,
+    WeaponReplaced,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiscardedCard {
+    pub card: Card,
+    pub resolution: Resolution,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiscardPile {
+    cards: Vec<DiscardedCard>,
+}
+
+impl DiscardPile {
+    pub fn new() -> Self {
+        return Self { cards: Vec::new() };
+    }
+
+    pub fn add(&mut self, card: Card, resolution: Resolution) {
+        self.cards.push(DiscardedCard { card, resolution });
+    }
+
+    pub fn len(&self) -> usize {
+        return self.cards.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.cards.is_empty();
+    }
+
+    pub fn last(&self) -> Option<&DiscardedCard> {
+        return self.cards.last();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DiscardedCard> {
+        return self.cards.iter();
+    }
+
+    pub fn cards(&self) -> &Vec<DiscardedCard> {
+        return &self.cards;
+    }
+}
+
+impl Default for DiscardPile {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
@@ -0,0 +1,136 @@
+use core::fmt;
+
+use crate::{
+    action::Action,
+    game::{Game, GameEvent, GameOutcome, Phase, Preview},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Play(Action),
+    Preview(Action),
+}
+
+#[derive(Debug)]
+pub enum InputError<E> {
+    // Shown to the player, then the driver asks again.
+    Invalid(E),
+    // Stops the driver, e.g. when the input stream was closed.
+    Fatal(E),
+}
+
+pub trait InputSource {
+    type Error: fmt::Display;
+
+    fn next_command(&mut self, game: &Game) -> Result<Command, InputError<Self::Error>>;
+}
+
+// Events, previews and errors arrive between two calls to `render`, a
+// frontend decides itself whether to show them right away or with the table.
+pub trait Renderer {
+    fn render(&mut self, game: &Game);
+
+    fn show_events(&mut self, events: &[GameEvent]);
+
+    fn show_preview(&mut self, preview: &Preview);
+
+    fn show_error(&mut self, error: &dyn fmt::Display);
+
+    fn show_rules(&mut self);
+
+    fn show_outcome(&mut self, outcome: &GameOutcome);
+}
+
+#[derive(Debug)]
+pub enum DriverExit<E> {
+    Finished(GameOutcome),
+    Quit,
+    InputFailed(E),
+}
+
+// The game loop every frontend shares: deal, render, read a command, apply
+// it, until the game ends or the player quits.
+pub struct GameDriver<I, R> {
+    game: Game,
+    input: I,
+    renderer: R,
+}
+
+impl<I: InputSource, R: Renderer> GameDriver<I, R> {
+    pub fn new(game: Game, input: I, renderer: R) -> Self {
+        return Self {
+            game,
+            input,
+            renderer,
+        };
+    }
+
+    pub fn game(&self) -> &Game {
+        return &self.game;
+    }
+
+    pub fn into_game(self) -> Game {
+        return self.game;
+    }
+
+    pub fn run(&mut self) -> DriverExit<I::Error> {
+        loop {
+            if let Some(outcome) = self.game.outcome() {
+                self.renderer.show_outcome(&outcome);
+                return DriverExit::Finished(outcome);
+            }
+
+            if self.game.phase() == Phase::TurnComplete {
+                let events = self.game.start_turn();
+                self.renderer.show_events(&events);
+                continue;
+            }
+
+            self.renderer.render(&self.game);
+
+            let command = match self.input.next_command(&self.game) {
+                Ok(command) => command,
+                Err(InputError::Invalid(e)) => {
+                    self.renderer.show_error(&e);
+                    continue;
+                }
+                Err(InputError::Fatal(e)) => return DriverExit::InputFailed(e),
+            };
+
+            match command {
+                Command::Preview(action) => match self.game.preview(&action) {
+                    Ok(preview) => self.renderer.show_preview(&preview),
+                    Err(e) => self.renderer.show_error(&e),
+                },
+
+                Command::Play(action) => {
+                    if let Some(exit) = self.play(action) {
+                        return exit;
+                    }
+                }
+            }
+        }
+    }
+
+    // Returns how the driver should stop, if the action ends the session.
+    fn play(&mut self, action: Action) -> Option<DriverExit<I::Error>> {
+        let events = match self.game.apply(action) {
+            Ok(events) => events,
+            Err(e) => {
+                self.renderer.show_error(&e);
+                return None;
+            }
+        };
+        self.renderer.show_events(&events);
+
+        for event in &events {
+            match event {
+                GameEvent::QuitGame => return Some(DriverExit::Quit),
+                GameEvent::RulesPrinted => self.renderer.show_rules(),
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
@@ -1,17 +1,81 @@
+use alloc::string::String;
+use core::fmt;
+
+use crate::{card::Card, deck::MAX_DECK_SIZE};
+
 #[derive(Debug)]
 pub enum GameError {
     RoomFull,
-    NotAWeapon,
-    NotAPotion,
+    NotAWeapon(Card),
+    NotAPotion(Card),
     CannotSkip,
     CannotSkipTwoInRow,
-    NotAMonster,
-    IndexOutOfBounds,
+    CannotSkipLastRoom,
+    NotAMonster(Card),
+    IndexOutOfBounds {
+        index: usize,
+        len: usize,
+    },
     NoWeaponEquipped,
-    MonsterTooStrongForWeapon,
+    // `strongest` is the strongest monster the weapon can still hit, derived
+    // from `last_slain` and the rules.
+    MonsterTooStrongForWeapon {
+        monster: Card,
+        last_slain: u8,
+        strongest: u8,
+    },
+    GameOver,
+    UndoDisabled,
+    NothingToUndo,
+    NothingToRedo,
+    CannotPreview,
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::RoomFull => write!(f, "The room is already full."),
+            GameError::NotAWeapon(card) => write!(f, "{card} is not a weapon."),
+            GameError::NotAPotion(card) => write!(f, "{card} is not a potion."),
+            GameError::CannotSkip => {
+                write!(f, "You cannot skip a room you already started playing.")
+            }
+            GameError::CannotSkipTwoInRow => write!(f, "You cannot skip two rooms in a row."),
+            GameError::CannotSkipLastRoom => {
+                write!(f, "You cannot skip the last room of the dungeon.")
+            }
+            GameError::NotAMonster(card) => write!(f, "{card} is not a monster."),
+            GameError::IndexOutOfBounds { index, len } => {
+                write!(
+                    f,
+                    "There is no card at position {}, the room holds {len} cards.",
+                    index + 1
+                )
+            }
+            GameError::NoWeaponEquipped => write!(f, "You must equip a weapon first."),
+            GameError::MonsterTooStrongForWeapon {
+                monster, strongest, ..
+            } => {
+                write!(
+                    f,
+                    "{monster} ({}) is too strong: your weapon can only hit monsters below {}.",
+                    monster.strength,
+                    strongest + 1
+                )
+            }
+            GameError::GameOver => write!(f, "The game is already over."),
+            GameError::UndoDisabled => write!(f, "Undo is disabled for this game."),
+            GameError::NothingToUndo => write!(f, "There is no move to undo."),
+            GameError::NothingToRedo => write!(f, "There is no move to redo."),
+            GameError::CannotPreview => write!(f, "Only moves can be previewed."),
+        }
+    }
 }
+
+#[cfg(feature = "std")]
 impl std::error::Error for GameError {}
 
+#[cfg(feature = "cli")]
 #[derive(Debug)]
 pub enum UiError {
     EmptyInput,
@@ -20,11 +84,273 @@ pub enum UiError {
     InvalidIndex,
     IndexStartsAtOne,
     InputReadFailed,
+    InputClosed,
 }
 
+#[cfg(feature = "cli")]
+impl fmt::Display for UiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            UiError::EmptyInput => "Please enter a command.",
+            UiError::UnknownCommand => "Unknown command.",
+            UiError::MissingIndex => "You must provide a card position.",
+            UiError::InvalidIndex => "There is no card at the given position.",
+            UiError::IndexStartsAtOne => "Card positions start at 1.",
+            UiError::InputReadFailed => "Failed to read input.",
+            UiError::InputClosed => "The input was closed.",
+        };
+
+        write!(f, "{msg}")
+    }
+}
+
+#[cfg(feature = "cli")]
+#[cfg(feature = "std")]
 impl std::error::Error for UiError {}
 
-pub enum AppError {
-    Ui(UiError),
-    Game(GameError),
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Malformed(serde_json::Error),
+    MissingVersion,
+    UnsupportedVersion(u32),
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "Could not access the save file: {e}"),
+            SaveError::Malformed(e) => write!(f, "The save file is damaged: {e}"),
+            SaveError::MissingVersion => write!(f, "The save file has no version."),
+            SaveError::UnsupportedVersion(v) => {
+                write!(f, "The save file version {v} is not supported.")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg(feature = "std")]
+impl std::error::Error for SaveError {}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    Malformed {
+        line: usize,
+    },
+    UnknownAction(String),
+    UnsupportedRules(u32),
+    Rules(RulesError),
+    DeckSpec(DeckSpecError),
+    Deal(DealError),
+    SeedUnsupported,
+    IllegalAction {
+        step: usize,
+        error: GameError,
+    },
+    Unfinished,
+    MissingScore,
+    ScoreMismatch {
+        claimed: i16,
+        actual: i16,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            ReplayError::Io(e) => write!(f, "Could not access the replay file: {e}"),
+            ReplayError::Malformed { line } => write!(f, "The replay is malformed at line {line}."),
+            ReplayError::UnknownAction(a) => {
+                write!(f, "The replay contains an unknown action: {a}")
+            }
+            ReplayError::UnsupportedRules(v) => {
+                write!(
+                    f,
+                    "The replay was recorded with unsupported rules version {v}."
+                )
+            }
+            ReplayError::DeckSpec(e) => write!(f, "The replay uses an invalid deck: {e}"),
+            ReplayError::Rules(e) => write!(f, "The replay uses invalid rules: {e}"),
+            ReplayError::Deal(e) => write!(f, "The replay uses an invalid deal: {e}"),
+            ReplayError::SeedUnsupported => {
+                write!(f, "Seeded replays need the rand-seeded feature.")
+            }
+            ReplayError::IllegalAction { step, error } => {
+                write!(f, "Step {step} of the replay is illegal: {error}")
+            }
+            ReplayError::Unfinished => write!(f, "The replayed game does not finish."),
+            ReplayError::MissingScore => write!(f, "The replay does not claim a score."),
+            ReplayError::ScoreMismatch { claimed, actual } => {
+                write!(
+                    f,
+                    "The replay claims a score of {claimed}, but it scores {actual}."
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReplayError {}
+
+#[derive(Debug)]
+pub enum RulesError {
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    UnknownRule(String),
+    InvalidValue(String),
+    InvalidHealth,
+    InvalidRoom,
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            RulesError::Io(e) => write!(f, "Could not read rules: {e}"),
+            RulesError::UnknownRule(name) => write!(f, "Unknown rule: {name}"),
+            RulesError::InvalidValue(pair) => write!(f, "Invalid rule value: {pair}"),
+            RulesError::InvalidHealth => {
+                write!(
+                    f,
+                    "Starting health must be between 1 and the maximum health."
+                )
+            }
+            RulesError::InvalidRoom => {
+                write!(f, "Cards to face must be between 1 and the room size.")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RulesError {}
+
+#[derive(Debug)]
+pub enum DeckSpecError {
+    EmptyDeck,
+    UnknownSuit { line: usize, suit: String },
+    MissingRanks { line: usize },
+    UnsupportedRank { line: usize, rank: String },
+    InvalidCount { line: usize, count: String },
+    UnexpectedToken { line: usize, token: String },
+    TooManyCards(usize),
 }
+
+impl fmt::Display for DeckSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeckSpecError::EmptyDeck => write!(f, "The deck spec contains no cards."),
+            DeckSpecError::UnknownSuit { line, suit } => {
+                write!(f, "Line {line}: unknown suit \"{suit}\".")
+            }
+            DeckSpecError::MissingRanks { line } => write!(f, "Line {line}: no ranks given."),
+            DeckSpecError::UnsupportedRank { line, rank } => {
+                write!(
+                    f,
+                    "Line {line}: unsupported rank \"{rank}\", use 2-10, J, Q, K or A."
+                )
+            }
+            DeckSpecError::InvalidCount { line, count } => {
+                write!(
+                    f,
+                    "Line {line}: invalid count \"{count}\", use x1, x2 and so on."
+                )
+            }
+            DeckSpecError::UnexpectedToken { line, token } => {
+                write!(f, "Line {line}: unexpected \"{token}\".")
+            }
+            DeckSpecError::TooManyCards(count) => {
+                write!(
+                    f,
+                    "The deck spec has {count} cards, at most {MAX_DECK_SIZE} are supported."
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DeckSpecError {}
+
+#[derive(Debug)]
+pub enum DealError {
+    EmptyDeal,
+    Card(CardParseError),
+    Rules(RulesError),
+    InvalidHealth(u8),
+    NotAWeapon(Card),
+    LastSlainWithoutWeapon,
+    InvalidSetup(String),
+    TooManyCards(usize),
+    Room(GameError),
+}
+
+impl fmt::Display for DealError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DealError::EmptyDeal => write!(f, "The deal contains no cards."),
+            DealError::Card(e) => write!(f, "{e}"),
+            DealError::Rules(e) => write!(f, "{e}"),
+            DealError::InvalidHealth(health) => {
+                write!(
+                    f,
+                    "Health {health} must be between 1 and the maximum health."
+                )
+            }
+            DealError::NotAWeapon(card) => write!(f, "{card} is not a weapon."),
+            DealError::LastSlainWithoutWeapon => {
+                write!(f, "A last slain strength needs a weapon to go with it.")
+            }
+            DealError::InvalidSetup(pair) => write!(f, "Invalid setup value: {pair}"),
+            DealError::TooManyCards(count) => {
+                write!(
+                    f,
+                    "The deal has {count} cards, at most {MAX_DECK_SIZE} are supported."
+                )
+            }
+            DealError::Room(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DealError {}
+
+#[derive(Debug)]
+pub enum CardParseError {
+    InvalidRank(u8),
+    UnknownRank(String),
+    UnknownSuit(String),
+    Malformed(String),
+}
+
+impl fmt::Display for CardParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardParseError::InvalidRank(value) => {
+                write!(
+                    f,
+                    "{value} is not a valid rank, numbered ranks go from 2 to 10."
+                )
+            }
+            CardParseError::UnknownRank(rank) => write!(f, "Unknown rank \"{rank}\"."),
+            CardParseError::UnknownSuit(suit) => write!(f, "Unknown suit \"{suit}\"."),
+            CardParseError::Malformed(card) => {
+                write!(
+                    f,
+                    "\"{card}\" is not a card, try something like SK, KS or ♠K."
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CardParseError {}
@@ -1,18 +1,117 @@
-use crate::{card::Card, deck::Deck, error::GameError, player::Player, room::Room, ui::Action};
+use alloc::{boxed::Box, format, string::ToString, vec, vec::Vec};
+use core::fmt;
 
+use crate::{
+    action::Action,
+    card::{Card, CardKind},
+    deck::{Composition, Deck},
+    discard::{DiscardPile, DiscardedCard, Resolution},
+    error::{DealError, GameError, RulesError},
+    observer::GameObserver,
+    player::{Player, WeaponReach},
+    room::Room,
+    rules::RuleSet,
+    view::{FullState, Observation},
+};
+
+#[derive(Debug, Clone)]
 pub enum GameEvent {
     QuitGame,
-    TurnEnded,
-    ActionApplied,
     RulesPrinted,
+    Undone,
+    Redone,
+    TurnStarted {
+        turn: u8,
+    },
+    TurnEnded,
+    RoomSkipped {
+        cards: Vec<Card>,
+    },
+    DamageTaken {
+        amount: u8,
+        source: Card,
+    },
+    MonsterSlain {
+        monster: Card,
+        weapon_strength: u8,
+    },
+    PotionConsumed {
+        potion: Card,
+        healed: u8,
+    },
+    PotionWasted {
+        potion: Card,
+        // How many potions work per turn.
+        limit: u8,
+    },
+    WeaponEquipped {
+        weapon: Card,
+        replaced: Option<Card>,
+    },
+    GameWon,
+    GameLost,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ending {
+    Died,
+    ClearedDungeon,
+    LeftLastRoom,
+}
+
+// Where the game stands between two calls to `apply`. Turns advance on their
+// own, so `TurnComplete` is only seen before `start_turn` deals the first room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    AwaitingRoomDecision,
+    ResolvingCards { remaining: usize },
+    TurnComplete,
+    GameOver,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preview {
+    pub health_change: i16,
+    pub health_after: u8,
+    pub reach_before: WeaponReach,
+    pub reach_after: WeaponReach,
+    pub potion_wasted: bool,
+    pub ends_turn: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreBreakdown {
+    pub health: u8,
+    pub deck_monster_penalty: u16,
+    pub room_monster_penalty: u16,
+    pub potion_bonus: u8,
+}
+
+impl ScoreBreakdown {
+    pub fn total(&self) -> i16 {
+        return self.health as i16 + self.potion_bonus as i16
+            - self.deck_monster_penalty as i16
+            - self.room_monster_penalty as i16;
+    }
 }
 
-pub enum GameOutcome {
-    Win { score: i16 },
-    Lose { score: i16 },
+#[derive(Debug, Clone)]
+pub struct GameOutcome {
+    pub ending: Ending,
+    pub score: i16,
+    pub breakdown: ScoreBreakdown,
+    pub seed: Option<u64>,
 }
 
+impl GameOutcome {
+    pub fn is_win(&self) -> bool {
+        return self.ending != Ending::Died;
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct GameInfo {
+    pub seed: Option<u64>,
     pub health: u8,
     pub remaining_cards: usize,
     pub weapon_strength: u8,
@@ -20,108 +119,637 @@ pub struct GameInfo {
     pub turn: u8,
     pub last_skipped: u8,
     pub room_cards: Vec<Card>,
+    pub discard: Vec<DiscardedCard>,
+    pub deck_composition: Composition,
+    pub known_bottom: Vec<Card>,
+}
+
+// Overrides for the player's starting state when dealing a puzzle, anything
+// left as `None` starts as the rules say.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DealSetup {
+    pub health: Option<u8>,
+    pub weapon: Option<Card>,
+    pub last_slain: Option<u8>,
+}
+
+impl DealSetup {
+    // Reads whitespace separated `name=value` pairs, e.g.
+    // `health=12 weapon=D7 last_slain=9`.
+    pub fn parse(text: &str) -> Result<Self, DealError> {
+        let mut setup = Self::default();
+
+        for pair in text.split_whitespace() {
+            let invalid = || DealError::InvalidSetup(pair.to_string());
+            let (name, value) = pair.split_once('=').ok_or_else(invalid)?;
+
+            match name {
+                "health" => setup.health = Some(value.parse().map_err(|_| invalid())?),
+                "weapon" => setup.weapon = Some(value.parse().map_err(DealError::Card)?),
+                "last_slain" => setup.last_slain = Some(value.parse().map_err(|_| invalid())?),
+                _ => return Err(invalid()),
+            }
+        }
+
+        return Ok(setup);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return *self == Self::default();
+    }
+}
+
+impl fmt::Display for DealSetup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut pairs = Vec::new();
+
+        if let Some(health) = self.health {
+            pairs.push(format!("health={health}"));
+        }
+
+        if let Some(weapon) = &self.weapon {
+            pairs.push(format!("weapon={weapon}"));
+        }
+
+        if let Some(last_slain) = self.last_slain {
+            pairs.push(format!("last_slain={last_slain}"));
+        }
+
+        write!(f, "{}", pairs.join(" "))
+    }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
+    rules: RuleSet,
     deck: Deck,
     room: Room,
     player: Player,
+    discard: DiscardPile,
+    setup: Option<DealSetup>,
 
     turn: u8,
     last_skipped_turn: u8,
+    last_resolved: Option<Card>,
+    actions: Vec<Action>,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    history: Vec<Snapshot>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    future: Vec<Snapshot>,
+    undo_limit: Option<usize>,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observers: Vec<Box<dyn GameObserver>>,
+}
+
+// The parts of a game that decide how it can still play out, as read by the
+// solver.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub(crate) struct Position {
+    pub(crate) deck: Vec<Card>,
+    pub(crate) room: Vec<Card>,
+    pub(crate) health: u8,
+    pub(crate) weapon_strength: u8,
+    pub(crate) last_slain: u8,
+    pub(crate) potions_this_turn: u8,
+    pub(crate) skipped_last_turn: bool,
+}
+
+#[derive(Clone)]
+struct Snapshot {
+    deck: Deck,
+    room: Room,
+    player: Player,
+    discard: DiscardPile,
+    turn: u8,
+    last_skipped_turn: u8,
+    last_resolved: Option<Card>,
+    actions: Vec<Action>,
 }
 
 impl Game {
+    #[cfg(all(feature = "rand-seeded", feature = "std"))]
     pub fn new() -> Self {
-        let deck = Deck::new();
-        let room = Room::new();
-        let player = Player::new();
+        return Self::with_deck(Deck::new(), RuleSet::default());
+    }
+
+    #[cfg(feature = "rand-seeded")]
+    pub fn with_seed(seed: u64) -> Self {
+        return Self::with_deck(Deck::with_seed(seed), RuleSet::default());
+    }
+
+    #[cfg(feature = "rand-seeded")]
+    pub fn with_rules(seed: u64, rules: RuleSet) -> Result<Self, RulesError> {
+        return Self::from_deck(Deck::with_seed(seed), rules);
+    }
+
+    pub fn from_deck(deck: Deck, rules: RuleSet) -> Result<Self, RulesError> {
+        rules.validate()?;
+
+        return Ok(Self::with_deck(deck, rules));
+    }
+
+    // Deals the written cards in order, e.g. `SK H5 D7 C2`, so a puzzle or a
+    // reported loss can be played out exactly.
+    pub fn from_deal(deal: &str, setup: DealSetup, rules: RuleSet) -> Result<Self, DealError> {
+        let cards = deal
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<Card>, _>>()
+            .map_err(DealError::Card)?;
+
+        return Self::from_cards(cards, setup, rules);
+    }
+
+    pub fn from_cards(
+        cards: Vec<Card>,
+        setup: DealSetup,
+        rules: RuleSet,
+    ) -> Result<Self, DealError> {
+        if cards.is_empty() {
+            return Err(DealError::EmptyDeal);
+        }
+
+        let mut game =
+            Self::from_deck(Deck::from_cards(cards)?, rules).map_err(DealError::Rules)?;
+
+        if let Some(health) = setup.health {
+            if health == 0 || health > rules.max_health {
+                return Err(DealError::InvalidHealth(health));
+            }
+
+            game.player.health = health;
+        }
+
+        if let Some(weapon) = &setup.weapon {
+            game.player
+                .equip_weapon(weapon)
+                .map_err(|_| DealError::NotAWeapon(weapon.clone()))?;
+        }
+
+        if let Some(last_slain) = setup.last_slain {
+            if setup.weapon.is_none() {
+                return Err(DealError::LastSlainWithoutWeapon);
+            }
+
+            game.player.weapon.last_slain_monster_strength = last_slain;
+        }
+
+        if !setup.is_empty() {
+            game.setup = Some(setup);
+        }
+
+        return Ok(game);
+    }
+
+    // Rebuilds a game from what a player can see, with `deck` as a guess at
+    // the draw order of the cards left. Search bots play their guesses out on
+    // it, so undo is off.
+    pub fn from_observation(obs: &Observation, deck: Vec<Card>) -> Result<Self, DealError> {
+        let mut game = Self::with_deck(Deck::from_cards(deck)?, obs.rules);
+
+        for card in &obs.room {
+            game.room.add(card.clone()).map_err(DealError::Room)?;
+        }
+
+        for discarded in &obs.discard {
+            game.discard
+                .add(discarded.card.clone(), discarded.resolution);
+        }
+
+        game.player = obs.player.clone();
+        game.turn = obs.turn;
+        game.last_skipped_turn = obs.last_skipped_turn;
+        game.last_resolved = obs.last_resolved.clone();
+        game.undo_limit = Some(0);
+
+        return Ok(game);
+    }
+
+    fn with_deck(deck: Deck, rules: RuleSet) -> Self {
+        let room = Room::new(&rules);
+        let player = Player::new(&rules);
 
         return Self {
+            rules,
             deck,
             room,
             player,
+            discard: DiscardPile::new(),
+            setup: None,
 
             turn: 0,
             last_skipped_turn: 0,
+            last_resolved: None,
+            actions: Vec::new(),
+
+            history: Vec::new(),
+            future: Vec::new(),
+            undo_limit: None,
+
+            observers: Vec::new(),
         };
     }
 
-    pub fn start_turn(&mut self) {
-        self.turn += 1;
-        self.fill_room();
+    // `None` keeps every move, `Some(0)` disables undo for competitive play.
+    pub fn set_undo_limit(&mut self, limit: Option<usize>) {
+        self.undo_limit = limit;
+        self.trim_history();
+    }
+
+    pub fn add_observer(&mut self, observer: Box<dyn GameObserver>) {
+        self.observers.push(observer);
+    }
+
+    // A copy to explore moves on. Observers, history, recorded actions and the
+    // discard pile are left behind as they do not change how the game plays out.
+    #[cfg(feature = "std")]
+    pub(crate) fn branch(&self) -> Self {
+        return Self {
+            rules: self.rules,
+            deck: self.deck.clone(),
+            room: self.room.clone(),
+            player: self.player.clone(),
+            discard: DiscardPile::new(),
+            setup: None,
+
+            turn: self.turn,
+            last_skipped_turn: self.last_skipped_turn,
+            last_resolved: self.last_resolved.clone(),
+            actions: Vec::new(),
+
+            history: Vec::new(),
+            future: Vec::new(),
+            undo_limit: Some(0),
+
+            observers: Vec::new(),
+        };
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn position(&self) -> Position {
+        let potions_this_turn = if self.player.last_healed_turn == self.turn {
+            self.player.potions_this_turn
+        } else {
+            0
+        };
+
+        return Position {
+            deck: self.deck.cards().to_vec(),
+            room: self.room.current_room().to_vec(),
+            health: self.player.health,
+            weapon_strength: self.player.weapon.strength,
+            last_slain: self.player.weapon.last_slain_monster_strength,
+            potions_this_turn,
+            skipped_last_turn: self.turn != 1 && self.turn - self.last_skipped_turn == 1,
+        };
+    }
+
+    // Deals the first room. Later turns are started by `apply` as soon as the
+    // previous one ends, so this does nothing once the game has begun.
+    pub fn start_turn(&mut self) -> Vec<GameEvent> {
+        if self.phase() != Phase::TurnComplete {
+            return Vec::new();
+        }
+
+        let events = self.deal_turn();
+
+        self.notify_turn_start(&events);
+        self.notify_ending(&events);
+
+        return events;
     }
 
-    pub fn apply(&mut self, action: Action) -> Result<GameEvent, GameError> {
+    pub fn apply(&mut self, action: Action) -> Result<Vec<GameEvent>, GameError> {
+        let result = self.apply_action(action);
+
+        for observer in &mut self.observers {
+            observer.on_action(&action, &result);
+        }
+
+        if let Ok(events) = &result {
+            self.notify_turn_start(events);
+            self.notify_ending(events);
+        }
+
+        return result;
+    }
+
+    pub fn phase(&self) -> Phase {
+        if self.is_over() {
+            return Phase::GameOver;
+        }
+
+        if self.turn == 0 {
+            return Phase::TurnComplete;
+        }
+
+        // A room is only whole until its first card is resolved, the same
+        // test skipping relies on.
+        if self.room.is_full() {
+            return Phase::AwaitingRoomDecision;
+        }
+
+        let remaining = if self.deck.is_empty() {
+            self.room.len()
+        } else {
+            self.room.len() - self.rules.cards_carried_over()
+        };
+
+        return Phase::ResolvingCards { remaining };
+    }
+
+    fn apply_action(&mut self, action: Action) -> Result<Vec<GameEvent>, GameError> {
         match action {
-            Action::Quit => Ok(GameEvent::QuitGame),
+            Action::Quit => Ok(vec![GameEvent::QuitGame]),
 
-            Action::Skip => {
-                match self.can_skip() {
-                    Ok(_) => {}
-                    Err(e) => return Err(e),
+            Action::PrintRules => Ok(vec![GameEvent::RulesPrinted]),
+
+            Action::Undo => {
+                self.undo()?;
+                Ok(vec![GameEvent::Undone])
+            }
+
+            Action::Redo => {
+                self.redo()?;
+                Ok(vec![GameEvent::Redone])
+            }
+
+            _ => {
+                if self.is_over() {
+                    return Err(GameError::GameOver);
                 }
 
-                self.room.clear_into(self.deck.cards_mut());
-                self.last_skipped_turn = self.turn;
+                let snapshot = self.snapshot();
+                let mut events = self.resolve(action)?;
+                self.actions.push(action);
+                self.push_ending(&mut events);
+
+                let turn_ended = events.iter().any(|e| matches!(e, GameEvent::TurnEnded));
+
+                if turn_ended && !self.is_over() {
+                    events.extend(self.deal_turn());
+                }
+
+                self.history.push(snapshot);
+                self.future.clear();
+                self.trim_history();
+
+                Ok(events)
+            }
+        }
+    }
+
+    pub fn undo(&mut self) -> Result<(), GameError> {
+        if self.undo_limit == Some(0) {
+            return Err(GameError::UndoDisabled);
+        }
+
+        let snapshot = self.history.pop().ok_or(GameError::NothingToUndo)?;
+        self.future.push(self.snapshot());
+        self.restore(snapshot);
+
+        Ok(())
+    }
+
+    pub fn redo(&mut self) -> Result<(), GameError> {
+        if self.undo_limit == Some(0) {
+            return Err(GameError::UndoDisabled);
+        }
+
+        let snapshot = self.future.pop().ok_or(GameError::NothingToRedo)?;
+        self.history.push(self.snapshot());
+        self.restore(snapshot);
 
-                Ok(GameEvent::TurnEnded)
+        Ok(())
+    }
+
+    fn resolve(&mut self, action: Action) -> Result<Vec<GameEvent>, GameError> {
+        let mut events = Vec::new();
+
+        match action {
+            Action::Quit | Action::PrintRules | Action::Undo | Action::Redo => {
+                unreachable!("meta actions are handled by apply")
             }
 
-            Action::PrintRules => Ok(GameEvent::RulesPrinted),
+            Action::Skip => {
+                self.can_skip()?;
+
+                events.push(GameEvent::RoomSkipped {
+                    cards: self.room.current_room().to_vec(),
+                });
+
+                self.room.clear_into(&mut self.deck);
+                self.last_skipped_turn = self.turn;
+
+                events.push(GameEvent::TurnEnded);
+                return Ok(events);
+            }
 
             Action::Fight { index } => {
                 let card = self.room.get(index)?;
-                self.player.fight(card)?;
-                self.room.remove(index)?;
+                let damage = self.player.fight(card)?;
+                let card = self.room.remove(index)?;
 
-                if self.room.len() == 1 {
-                    Ok(GameEvent::TurnEnded)
-                } else {
-                    Ok(GameEvent::ActionApplied)
+                if damage > 0 {
+                    events.push(GameEvent::DamageTaken {
+                        amount: damage,
+                        source: card.clone(),
+                    });
                 }
+
+                self.last_resolved = Some(card.clone());
+                self.discard.add(card, Resolution::Fought);
             }
 
             Action::Kill { index } => {
                 let card = self.room.get(index)?;
-                self.player.kill(card)?;
-                self.room.remove(index)?;
+                let damage = self.player.kill(card, &self.rules)?;
+                let card = self.room.remove(index)?;
 
-                if self.room.len() == 1 {
-                    Ok(GameEvent::TurnEnded)
-                } else {
-                    Ok(GameEvent::ActionApplied)
+                if damage > 0 {
+                    events.push(GameEvent::DamageTaken {
+                        amount: damage,
+                        source: card.clone(),
+                    });
                 }
+
+                events.push(GameEvent::MonsterSlain {
+                    monster: card.clone(),
+                    weapon_strength: self.player.weapon.strength,
+                });
+
+                self.last_resolved = Some(card.clone());
+                self.discard.add(card, Resolution::Slain);
             }
 
             Action::Heal { index } => {
                 let card = self.room.get(index)?;
-                self.player.heal(card, self.turn)?;
-                self.room.remove(index)?;
+                let health = self.player.health;
+                let drunk = self.player.heal(card, self.turn, &self.rules)?;
+                let card = self.room.remove(index)?;
+                self.last_resolved = Some(card.clone());
 
-                if self.room.len() == 1 {
-                    Ok(GameEvent::TurnEnded)
+                if drunk {
+                    events.push(GameEvent::PotionConsumed {
+                        potion: card.clone(),
+                        healed: self.player.health - health,
+                    });
+                    self.discard.add(card, Resolution::PotionDrunk);
                 } else {
-                    Ok(GameEvent::ActionApplied)
+                    events.push(GameEvent::PotionWasted {
+                        potion: card.clone(),
+                        limit: self.rules.potions_per_turn,
+                    });
+                    self.discard.add(card, Resolution::PotionWasted);
                 }
             }
 
             Action::Equip { index } => {
                 let card = self.room.get(index)?;
-                self.player.equip_weapon(card)?;
-                self.room.remove(index)?;
+                let replaced = self.player.equip_weapon(card)?;
+                let card = self.room.remove(index)?;
+                self.last_resolved = Some(card.clone());
 
-                if self.room.len() == 1 {
-                    Ok(GameEvent::TurnEnded)
-                } else {
-                    Ok(GameEvent::ActionApplied)
+                events.push(GameEvent::WeaponEquipped {
+                    weapon: card,
+                    replaced: replaced.clone(),
+                });
+
+                if let Some(weapon) = replaced {
+                    self.discard.add(weapon, Resolution::WeaponReplaced);
                 }
             }
         }
+
+        if self.turn_ends_at(self.room.len()) {
+            events.push(GameEvent::TurnEnded);
+        }
+
+        return Ok(events);
+    }
+
+    pub fn preview(&self, action: &Action) -> Result<Preview, GameError> {
+        if self.is_over() {
+            return Err(GameError::GameOver);
+        }
+
+        let mut player = self.player.clone();
+        let mut potion_wasted = false;
+
+        match *action {
+            Action::Quit | Action::PrintRules | Action::Undo | Action::Redo => {
+                return Err(GameError::CannotPreview);
+            }
+
+            Action::Skip => {
+                self.can_skip()?;
+
+                return Ok(Preview {
+                    health_change: 0,
+                    health_after: player.health,
+                    reach_before: player.reach(&self.rules),
+                    reach_after: player.reach(&self.rules),
+                    potion_wasted,
+                    ends_turn: true,
+                });
+            }
+
+            Action::Fight { index } => {
+                player.fight(self.room.get(index)?)?;
+            }
+
+            Action::Kill { index } => {
+                player.kill(self.room.get(index)?, &self.rules)?;
+            }
+
+            Action::Heal { index } => {
+                potion_wasted = !player.heal(self.room.get(index)?, self.turn, &self.rules)?;
+            }
+
+            Action::Equip { index } => {
+                player.equip_weapon(self.room.get(index)?)?;
+            }
+        }
+
+        return Ok(Preview {
+            health_change: player.health as i16 - self.player.health as i16,
+            health_after: player.health,
+            reach_before: self.player.reach(&self.rules),
+            reach_after: player.reach(&self.rules),
+            potion_wasted,
+            ends_turn: self.turn_ends_at(self.room.len() - 1),
+        });
+    }
+
+    // Once the deck is empty the final room has to be cleared completely.
+    fn turn_ends_at(&self, room_len: usize) -> bool {
+        if self.deck.is_empty() {
+            room_len == 0
+        } else {
+            room_len == self.rules.cards_carried_over()
+        }
+    }
+
+    // Meta actions (quit, rules, undo and redo) are not listed.
+    pub fn legal_actions(&self) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        if self.is_over() {
+            return actions;
+        }
+
+        if self.can_skip().is_ok() {
+            actions.push(Action::Skip);
+        }
+
+        for (index, card) in self.room.iter().enumerate() {
+            match card.kind {
+                CardKind::Monster => {
+                    actions.push(Action::Fight { index });
+
+                    if self.player.can_kill(card, &self.rules).is_ok() {
+                        actions.push(Action::Kill { index });
+                    }
+                }
+                CardKind::Weapon => actions.push(Action::Equip { index }),
+                CardKind::Potion => actions.push(Action::Heal { index }),
+            }
+        }
+
+        return actions;
+    }
+
+    pub fn observation(&self) -> Observation {
+        return Observation {
+            rules: self.rules,
+            turn: self.turn,
+            last_skipped_turn: self.last_skipped_turn,
+            player: self.player.clone(),
+            room: self.room.current_room().to_vec(),
+            deck_size: self.deck.len(),
+            deck_composition: self.deck.composition(),
+            known_bottom: self.deck.known_bottom().to_vec(),
+            discard: self.discard.cards().to_vec(),
+            last_resolved: self.last_resolved.clone(),
+            legal_actions: self.legal_actions(),
+        };
+    }
+
+    pub fn full_state(&self) -> FullState {
+        return FullState {
+            observation: self.observation(),
+            deck: self.deck.draw_order(),
+            seed: self.deck.seed(),
+        };
     }
 
     pub fn game_info(&self) -> GameInfo {
         return GameInfo {
+            seed: self.deck.seed(),
             room_cards: self.room.current_room().to_vec(),
             turn: self.turn,
             health: self.player.health,
@@ -129,39 +757,181 @@ impl Game {
             remaining_cards: self.deck.len(),
             last_skipped: self.last_skipped_turn,
             weapon_strength: self.player.weapon.strength,
+            discard: self.discard.cards().to_vec(),
+            deck_composition: self.deck.composition(),
+            known_bottom: self.deck.known_bottom().to_vec(),
         };
     }
 
+    pub fn rules(&self) -> &RuleSet {
+        return &self.rules;
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        return self.deck.seed();
+    }
+
+    pub fn room(&self) -> &[Card] {
+        return self.room.current_room();
+    }
+
+    pub fn deck_spec(&self) -> Option<&str> {
+        return self.deck.spec();
+    }
+
+    // The explicit card order for games built with `from_deal`.
+    pub fn dealt(&self) -> Option<&[Card]> {
+        return self.deck.dealt();
+    }
+
+    pub fn setup(&self) -> Option<&DealSetup> {
+        return self.setup.as_ref();
+    }
+
+    pub fn actions(&self) -> &[Action] {
+        return &self.actions;
+    }
+
     pub fn is_over(&self) -> bool {
-        return self.deck.is_empty() || self.player.health == 0;
+        return self.ending().is_some();
     }
 
-    pub fn outcome(&self) -> Option<GameOutcome> {
-        if !self.is_over() {
-            return None;
+    pub fn ending(&self) -> Option<Ending> {
+        if self.player.health == 0 {
+            return Some(Ending::Died);
         }
 
-        let score = self.calculate_score();
+        if !self.deck.is_empty() {
+            return None;
+        }
 
-        if self.player.health == 0 {
-            return Some(GameOutcome::Lose { score });
+        if self.room.is_empty() {
+            Some(Ending::ClearedDungeon)
+        } else if self.rules.leave_last_room {
+            Some(Ending::LeftLastRoom)
         } else {
-            Some(GameOutcome::Win { score })
+            None
         }
     }
 
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        let ending = self.ending()?;
+
+        let breakdown = self.score_breakdown(ending);
+
+        return Some(GameOutcome {
+            ending,
+            score: breakdown.total(),
+            breakdown,
+            seed: self.deck.seed(),
+        });
+    }
+
     fn can_skip(&self) -> Result<(), GameError> {
         if !self.room.is_full() {
             return Err(GameError::CannotSkip);
         }
 
-        if self.turn != 1 && self.turn - self.last_skipped_turn == 1 {
+        if self.deck.is_empty() {
+            return Err(GameError::CannotSkipLastRoom);
+        }
+
+        if !self.rules.consecutive_skips
+            && self.turn != 1
+            && self.turn - self.last_skipped_turn == 1
+        {
             return Err(GameError::CannotSkipTwoInRow);
         }
 
         Ok(())
     }
 
+    fn deal_turn(&mut self) -> Vec<GameEvent> {
+        // Only endless skips under `consecutive_skips` get this far.
+        self.turn = self.turn.saturating_add(1);
+        self.fill_room();
+
+        let mut events = vec![GameEvent::TurnStarted { turn: self.turn }];
+        self.push_ending(&mut events);
+
+        return events;
+    }
+
+    fn notify_turn_start(&mut self, events: &[GameEvent]) {
+        if !events
+            .iter()
+            .any(|e| matches!(e, GameEvent::TurnStarted { .. }))
+        {
+            return;
+        }
+
+        let info = self.game_info();
+        for observer in &mut self.observers {
+            observer.on_turn_start(&info);
+        }
+    }
+
+    fn notify_ending(&mut self, events: &[GameEvent]) {
+        let ended = events
+            .iter()
+            .any(|e| matches!(e, GameEvent::GameWon | GameEvent::GameLost));
+
+        if !ended {
+            return;
+        }
+
+        if let Some(outcome) = self.outcome() {
+            for observer in &mut self.observers {
+                observer.on_game_end(&outcome);
+            }
+        }
+    }
+
+    fn push_ending(&self, events: &mut Vec<GameEvent>) {
+        match self.ending() {
+            Some(Ending::Died) => events.push(GameEvent::GameLost),
+            Some(_) => events.push(GameEvent::GameWon),
+            None => {}
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        return Snapshot {
+            deck: self.deck.clone(),
+            room: self.room.clone(),
+            player: self.player.clone(),
+            discard: self.discard.clone(),
+            turn: self.turn,
+            last_skipped_turn: self.last_skipped_turn,
+            last_resolved: self.last_resolved.clone(),
+            actions: self.actions.clone(),
+        };
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.deck = snapshot.deck;
+        self.room = snapshot.room;
+        self.player = snapshot.player;
+        self.discard = snapshot.discard;
+        self.turn = snapshot.turn;
+        self.last_skipped_turn = snapshot.last_skipped_turn;
+        self.last_resolved = snapshot.last_resolved;
+        self.actions = snapshot.actions;
+    }
+
+    fn trim_history(&mut self) {
+        if let Some(limit) = self.undo_limit
+            && self.history.len() > limit
+        {
+            let excess = self.history.len() - limit;
+            self.history.drain(..excess);
+        }
+
+        if self.undo_limit == Some(0) {
+            self.future.clear();
+        }
+    }
+
     fn fill_room(&mut self) {
         while !self.room.is_full() {
             match self.deck.draw() {
@@ -173,11 +943,106 @@ impl Game {
         }
     }
 
-    fn calculate_score(&self) -> i16 {
-        if self.deck.is_empty() {
-            self.player.health as i16
-        } else {
-            self.deck.remaining_monster_strength() as i16
+    // A death scores the negative strength of every monster still in the
+    // dungeon. Surviving scores the remaining health, plus the value of the
+    // last card if it was a potion taken at full health.
+    fn score_breakdown(&self, ending: Ending) -> ScoreBreakdown {
+        if ending == Ending::Died {
+            return ScoreBreakdown {
+                health: 0,
+                deck_monster_penalty: self.deck.remaining_monster_strength(),
+                room_monster_penalty: self.room.remaining_monster_strength(),
+                potion_bonus: 0,
+            };
         }
+
+        let potion_bonus = match &self.last_resolved {
+            Some(card)
+                if matches!(card.kind, CardKind::Potion)
+                    && self.player.health == self.rules.max_health =>
+            {
+                card.strength
+            }
+            _ => 0,
+        };
+
+        return ScoreBreakdown {
+            health: self.player.health,
+            deck_monster_penalty: 0,
+            room_monster_penalty: 0,
+            potion_bonus,
+        };
+    }
+}
+
+#[cfg(all(feature = "rand-seeded", feature = "std"))]
+impl Default for Game {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+#[cfg(all(test, feature = "rand-seeded"))]
+mod tests {
+    use super::*;
+
+    // Plays a line of legal actions, and at every step tries each action on
+    // a branch of the game to check `legal_actions`, `apply` and `preview`
+    // agree on what may be played.
+    fn check_legal_actions(rules: RuleSet) {
+        for seed in 0..20 {
+            let mut game = Game::with_rules(seed, rules).unwrap();
+            game.start_turn();
+            let mut step = seed as usize;
+
+            while !game.is_over() {
+                let legal = game.legal_actions();
+                let mut candidates = vec![Action::Skip];
+
+                for index in 0..=rules.room_size {
+                    candidates.extend([
+                        Action::Fight { index },
+                        Action::Kill { index },
+                        Action::Equip { index },
+                        Action::Heal { index },
+                    ]);
+                }
+
+                for action in candidates {
+                    let expected = legal.contains(&action);
+
+                    assert_eq!(game.branch().apply(action).is_ok(), expected, "{action:?}");
+                    assert_eq!(game.preview(&action).is_ok(), expected, "{action:?}");
+                }
+
+                step = (step * 31 + 7) % 1009;
+                game.apply(legal[step % legal.len()]).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn legal_actions_match_apply_official() {
+        check_legal_actions(RuleSet::official());
+    }
+
+    #[test]
+    fn legal_actions_match_apply_house_rules() {
+        check_legal_actions(RuleSet {
+            consecutive_skips: true,
+            slay_equal_strength: true,
+            leave_last_room: true,
+            ..RuleSet::official()
+        });
+    }
+
+    #[test]
+    fn legal_actions_match_apply_larger_rooms() {
+        check_legal_actions(RuleSet {
+            room_size: 5,
+            cards_to_face: 4,
+            potions_per_turn: 2,
+            ..RuleSet::official()
+        });
     }
 }
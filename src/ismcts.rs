@@ -0,0 +1,268 @@
+use std::time::{Duration, Instant};
+
+use rand::{Rng, SeedableRng, seq::SliceRandom};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    action::Action,
+    card::{Card, CardKind, Rank, Suit},
+    game::{Game, GameOutcome},
+    strategy::{self, Strategy, WeaponKeeper},
+    view::Observation,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    Iterations(u32),
+    Time(Duration),
+}
+
+// Information set Monte Carlo tree search. Every iteration guesses an order
+// for the unseen cards, consistent with the deck composition and the skipped
+// rooms known to be at the bottom, and plays it out on a rebuilt game. Nodes
+// stand for what the player has seen, so guesses that deal the same room share
+// a node and no guess ever leaks into the choice of move.
+pub struct Ismcts {
+    budget: Budget,
+    exploration: f64,
+    rng: ChaCha8Rng,
+    rollout: Box<dyn Strategy>,
+}
+
+#[derive(Default)]
+struct Node {
+    edges: Vec<Edge>,
+}
+
+struct Edge {
+    action: Action,
+    visits: u32,
+    // How often the action was legal when this node was passed through, as
+    // unlikely actions should not be pushed for being rarely tried.
+    available: u32,
+    reward: f64,
+    // The nodes reached, by the room the action left behind.
+    children: Vec<(Vec<Card>, usize)>,
+}
+
+impl Ismcts {
+    // Plays out each guess with `WeaponKeeper`, which is far stronger than
+    // random moves for the little time it takes.
+    pub fn new(budget: Budget, seed: u64) -> Self {
+        return Self {
+            budget,
+            exploration: 0.7,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            rollout: Box::new(WeaponKeeper),
+        };
+    }
+
+    pub fn with_rollout(mut self, rollout: Box<dyn Strategy>) -> Self {
+        self.rollout = rollout;
+        return self;
+    }
+
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        return self;
+    }
+
+    fn search(&mut self, obs: &Observation) -> Action {
+        let started = Instant::now();
+        let worst = Self::monster_strength(obs);
+        let mut nodes = vec![Node::default()];
+        let mut iterations = 0;
+
+        loop {
+            let done = match self.budget {
+                Budget::Iterations(limit) => iterations >= limit.max(1),
+                Budget::Time(limit) => iterations > 0 && started.elapsed() >= limit,
+            };
+
+            if done {
+                break;
+            }
+
+            self.iterate(obs, &mut nodes, worst);
+            iterations += 1;
+        }
+
+        return nodes[0]
+            .edges
+            .iter()
+            .filter(|edge| obs.legal_actions.contains(&edge.action))
+            .max_by_key(|edge| edge.visits)
+            .map(|edge| edge.action)
+            .unwrap_or(obs.legal_actions[0]);
+    }
+
+    fn iterate(&mut self, obs: &Observation, nodes: &mut Vec<Node>, worst: u16) {
+        let deck = self.guess_deck(obs);
+        let Ok(mut game) = Game::from_observation(obs, deck) else {
+            return;
+        };
+
+        let mut node = 0;
+        let mut path = Vec::new();
+
+        while !game.is_over() {
+            let legal = game.legal_actions();
+
+            for action in &legal {
+                let edges = &mut nodes[node].edges;
+
+                match edges.iter_mut().find(|edge| edge.action == *action) {
+                    Some(edge) => edge.available += 1,
+                    None => edges.push(Edge {
+                        action: *action,
+                        visits: 0,
+                        available: 1,
+                        reward: 0.0,
+                        children: Vec::new(),
+                    }),
+                }
+            }
+
+            let untried: Vec<usize> = (0..nodes[node].edges.len())
+                .filter(|&index| {
+                    let edge = &nodes[node].edges[index];
+                    edge.visits == 0 && legal.contains(&edge.action)
+                })
+                .collect();
+
+            let expanding = !untried.is_empty();
+            let edge = match untried.choose(&mut self.rng) {
+                Some(&index) => index,
+                None => self.select(&nodes[node], &legal),
+            };
+
+            if game.apply(nodes[node].edges[edge].action).is_err() {
+                break;
+            }
+            path.push((node, edge));
+
+            if expanding {
+                break;
+            }
+
+            let room = game.room();
+            let child = nodes[node].edges[edge]
+                .children
+                .iter()
+                .find(|(seen, _)| seen.as_slice() == room)
+                .map(|(_, child)| *child);
+
+            node = match child {
+                Some(child) => child,
+                None => {
+                    nodes.push(Node::default());
+                    let child = nodes.len() - 1;
+                    nodes[node].edges[edge]
+                        .children
+                        .push((room.to_vec(), child));
+                    child
+                }
+            };
+        }
+
+        let reward = match strategy::play(&mut game, &mut self.rollout) {
+            Ok(outcome) => Self::reward(&outcome, worst, obs),
+            Err(_) => return,
+        };
+
+        for (node, edge) in path {
+            let edge = &mut nodes[node].edges[edge];
+            edge.visits += 1;
+            edge.reward += reward;
+        }
+    }
+
+    // UCB1, with the number of times the action was available in place of
+    // the visits to the node.
+    fn select(&mut self, node: &Node, legal: &[Action]) -> usize {
+        let mut best = 0;
+        let mut best_value = f64::MIN;
+
+        for (index, edge) in node.edges.iter().enumerate() {
+            if !legal.contains(&edge.action) {
+                continue;
+            }
+
+            let visits = edge.visits as f64;
+            let value = edge.reward / visits
+                + self.exploration * ((edge.available as f64).ln() / visits).sqrt()
+                // Breaks ties without favouring the first action.
+                + self.rng.gen_range(0.0..1e-6);
+
+            if value > best_value {
+                best = index;
+                best_value = value;
+            }
+        }
+
+        return best;
+    }
+
+    // Wins score above every loss: a win maps to 0.5 and up by its score, a
+    // loss to below 0.5 by how much of the dungeon was left.
+    fn reward(outcome: &GameOutcome, worst: u16, obs: &Observation) -> f64 {
+        if outcome.is_win() {
+            let best = obs.rules.max_health as f64 + 14.0;
+            return 0.5 + 0.5 * (outcome.score as f64 / best).min(1.0);
+        }
+
+        let left = -(outcome.score as f64);
+        return 0.5 * (1.0 - left / (worst.max(1) as f64));
+    }
+
+    // The strength of every monster not yet slain or fought, i.e. the score
+    // of dying right away.
+    fn monster_strength(obs: &Observation) -> u16 {
+        let room: u16 = obs
+            .room
+            .iter()
+            .filter(|card| card.kind == CardKind::Monster)
+            .map(|card| card.strength as u16)
+            .sum();
+
+        return room + obs.deck_composition.total_strength(CardKind::Monster);
+    }
+
+    // The unseen cards in a random order, then the known bottom cards in
+    // theirs. Suits only tell the kinds apart, so any suit of the kind will do.
+    fn guess_deck(&mut self, obs: &Observation) -> Vec<Card> {
+        let unknown = obs.deck_composition.excluding(&obs.known_bottom);
+        let mut cards = Vec::with_capacity(obs.deck_size);
+
+        for (kind, suit) in [
+            (CardKind::Monster, Suit::Spades),
+            (CardKind::Weapon, Suit::Diamonds),
+            (CardKind::Potion, Suit::Hearts),
+        ] {
+            for (strength, count) in unknown.counts(kind) {
+                let Ok(rank) = Rank::from_strength(*strength) else {
+                    continue;
+                };
+
+                for _ in 0..*count {
+                    cards.push(Card::new(suit, rank));
+                }
+            }
+        }
+
+        cards.shuffle(&mut self.rng);
+        cards.extend(obs.known_bottom.iter().rev().cloned());
+
+        return cards;
+    }
+}
+
+impl Strategy for Ismcts {
+    fn choose(&mut self, obs: &Observation) -> Action {
+        if obs.legal_actions.len() == 1 {
+            return obs.legal_actions[0];
+        }
+
+        return self.search(obs);
+    }
+}
@@ -1,7 +1,26 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod action;
 pub mod card;
 pub mod deck;
+pub mod discard;
+pub mod driver;
 pub mod error;
 pub mod game;
+#[cfg(all(feature = "std", feature = "rand-seeded"))]
+pub mod ismcts;
+pub mod observer;
 pub mod player;
+pub mod replay;
 pub mod room;
+pub mod rules;
+#[cfg(feature = "serde")]
+pub mod save;
+#[cfg(feature = "std")]
+pub mod solver;
+pub mod strategy;
+#[cfg(feature = "cli")]
 pub mod ui;
+pub mod view;
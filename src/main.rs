@@ -1,80 +1,317 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
 use scoundrel::{
-    error::AppError,
-    game::{Game, GameEvent},
-    ui::{Parser, Printer, Reader},
+    deck::Deck,
+    driver::{DriverExit, GameDriver},
+    error::UiError,
+    game::{DealSetup, Ending, Game, GameEvent},
+    replay::Replay,
+    rules::RuleSet,
+    save,
+    solver::Solver,
+    ui::{Printer, Reader, TerminalInput},
 };
 
+struct Options {
+    seed: Option<u64>,
+    undo_limit: Option<usize>,
+    save_path: PathBuf,
+    rules_path: Option<PathBuf>,
+    deck_path: Option<PathBuf>,
+    deal_path: Option<PathBuf>,
+    setup: DealSetup,
+    record_path: Option<PathBuf>,
+    replay_path: Option<PathBuf>,
+    verify: bool,
+    solve: bool,
+}
+
 fn main() {
-    let mut game = Game::new();
-    let mut printer = Printer::new();
+    let options = parse_args();
 
-    'game: loop {
-        Printer::clear_screen();
-        game.start_turn();
+    if let Some(path) = &options.replay_path {
+        run_replay(path, options.verify);
+        return;
+    }
 
-        if game.is_over() {
-            break 'game;
-        }
+    if options.solve {
+        run_solve(&options);
+        return;
+    }
 
-        'turn: loop {
-            Printer::clear_screen();
-            Printer::print_room(game.game_info());
-            printer.print_errors();
+    let saved = load_saved_game(&options.save_path);
+    let resumed = saved.is_some();
 
-            let input = match Reader::read_input() {
-                Ok(i) => i,
+    let game = match saved {
+        Some(game) => game,
+        None => {
+            let mut game = match new_game(&options) {
+                Ok(game) => game,
                 Err(e) => {
-                    eprintln!("Fatal input error: {}", e);
-                    break 'game;
+                    eprintln!("{}", e);
+                    std::process::exit(1);
                 }
             };
+            game.set_undo_limit(options.undo_limit);
+            game
+        }
+    };
 
-            let action = match Parser::parse_action(&input) {
-                Ok(a) => a,
-                Err(e) => {
-                    printer.add_error(AppError::Ui(e));
-                    continue 'turn;
-                }
-            };
+    let mut driver = GameDriver::new(game, TerminalInput, Printer::new());
+    let exit = driver.run();
+    let game = driver.into_game();
 
-            match game.apply(action) {
-                Ok(GameEvent::TurnEnded) => {
-                    if game.is_over() {
-                        break 'game;
-                    }
+    match exit {
+        // A save the player did not resume is still theirs to come back to.
+        DriverExit::Finished(_) => {
+            if resumed {
+                let _ = fs::remove_file(&options.save_path);
+            }
+        }
+        DriverExit::Quit => match save::write(&game, &options.save_path) {
+            Ok(_) => println!("Game saved to {}", options.save_path.display()),
+            Err(e) => eprintln!("{}", e),
+        },
+        DriverExit::InputFailed(e) => eprintln!("Fatal input error: {}", e),
+    }
 
-                    break 'turn;
-                }
+    if let Some(path) = &options.record_path
+        && let Err(e) = Replay::from_game(&game).write(path)
+    {
+        eprintln!("{}", e);
+    }
+}
 
-                Ok(GameEvent::RulesPrinted) => {
-                    Printer::clear_screen();
-                    Printer::print_rules();
-
-                    match Reader::read_input() {
-                        Ok(_) => {}
-                        Err(e) => {
-                            eprintln!("Fatal input error: {}", e);
-                            break 'game;
-                        }
-                    };
-                }
+fn new_game(options: &Options) -> Result<Game, String> {
+    let rules = match &options.rules_path {
+        Some(path) => RuleSet::load(path).map_err(|e| e.to_string())?,
+        None => RuleSet::official(),
+    };
 
-                Ok(GameEvent::ActionApplied) => {}
+    match &options.deal_path {
+        Some(path) => load_deal(path, options.setup.clone(), rules),
+        None => {
+            let seed = options.seed.unwrap_or_else(rand::random);
 
-                Ok(GameEvent::QuitGame) => break 'game,
-                Err(e) => {
-                    printer.add_error(AppError::Game(e));
-                    continue 'turn;
-                }
+            load_deck(options.deck_path.as_deref(), seed)
+                .and_then(|deck| Game::from_deck(deck, rules).map_err(|e| e.to_string()))
+        }
+    }
+}
+
+fn run_solve(options: &Options) {
+    let mut game = match new_game(options) {
+        Ok(game) => game,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let solution = Solver::new().solve(&game);
+
+    // Plays the line on the real game to split it into turns, and so that
+    // it can be recorded like any other game.
+    game.start_turn();
+    let mut turns = vec![Vec::new()];
+
+    for action in &solution.line {
+        let events = match game.apply(*action) {
+            Ok(events) => events,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
             }
+        };
+
+        if let (Some(turn), Some(notation)) = (turns.last_mut(), action.notation()) {
+            turn.push(notation);
+        }
+
+        if !game.is_over() && events.iter().any(|e| matches!(e, GameEvent::TurnEnded)) {
+            turns.push(Vec::new());
+        }
+    }
+
+    if let Some(seed) = game.seed() {
+        println!("Seed: {}", seed);
+    }
+
+    for (index, turn) in turns.iter().enumerate() {
+        println!("Turn {:>2}: {}", index + 1, turn.join(" "));
+    }
+
+    let ending = match solution.ending {
+        Ending::ClearedDungeon => "clears the dungeon",
+        Ending::LeftLastRoom => "leaves the last room behind",
+        Ending::Died => "dies in the dungeon",
+    };
+
+    println!();
+    println!("Best score: {}, the line above {}.", solution.score, ending);
+    println!("Searched {} turns.", solution.positions);
+
+    if let Some(path) = &options.record_path
+        && let Err(e) = Replay::from_game(&game).write(path)
+    {
+        eprintln!("{}", e);
+    }
+}
 
-            if game.is_over() {
-                break 'game;
+fn run_replay(path: &Path, verify: bool) {
+    let replay = match Replay::read(path) {
+        Ok(replay) => replay,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if verify {
+        match replay.verify() {
+            Ok(score) => println!("Replay verified, score: {}", score),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+
+        return;
+    }
+
+    let frames = match replay.frames() {
+        Ok(frames) => frames,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let mut step = 0;
+
+    loop {
+        Printer::clear_screen();
+        Printer::print_room(frames[step].clone());
+        Printer::print_replay_controls(step, frames.len() - 1);
+
+        let input = match Reader::read_input() {
+            Ok(i) => i,
+            Err(UiError::InputClosed) => break,
+            Err(e) => {
+                eprintln!("Fatal input error: {}", e);
+                break;
+            }
+        };
+
+        match input.as_str() {
+            "" | "n" => step = (step + 1).min(frames.len() - 1),
+            "b" => step = step.saturating_sub(1),
+            "q" => break,
+            _ => {}
+        }
+    }
+}
+
+fn load_deck(path: Option<&Path>, seed: u64) -> Result<Deck, String> {
+    let Some(path) = path else {
+        return Ok(Deck::with_seed(seed));
+    };
+
+    let spec = fs::read_to_string(path).map_err(|e| format!("Could not read deck: {e}"))?;
+
+    Deck::from_spec(&spec, seed).map_err(|e| e.to_string())
+}
+
+fn load_deal(path: &Path, setup: DealSetup, rules: RuleSet) -> Result<Game, String> {
+    let deal = fs::read_to_string(path).map_err(|e| format!("Could not read deal: {e}"))?;
+
+    Game::from_deal(&deal, setup, rules).map_err(|e| e.to_string())
+}
+
+fn load_saved_game(path: &Path) -> Option<Game> {
+    if !path.exists() {
+        return None;
+    }
+
+    println!("A saved game was found. Resume it? (y/n)");
+
+    let answer = Reader::read_input().ok()?;
+
+    if !answer.eq_ignore_ascii_case("y") {
+        return None;
+    }
+
+    match save::read(path) {
+        Ok(game) => Some(game),
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        }
+    }
+}
+
+fn parse_args() -> Options {
+    let mut options = Options {
+        seed: None,
+        undo_limit: None,
+        save_path: PathBuf::from("scoundrel.save"),
+        rules_path: None,
+        deck_path: None,
+        deal_path: None,
+        setup: DealSetup::default(),
+        record_path: None,
+        replay_path: None,
+        verify: false,
+        solve: false,
+    };
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => options.seed = Some(parse_value(&arg, args.next())),
+            "--undo-limit" => options.undo_limit = Some(parse_value(&arg, args.next())),
+            "--no-undo" => options.undo_limit = Some(0),
+            "--save" => {
+                if let Some(path) = args.next() {
+                    options.save_path = PathBuf::from(path);
+                }
             }
+            "--rules" => options.rules_path = args.next().map(PathBuf::from),
+            "--deck" => options.deck_path = args.next().map(PathBuf::from),
+            "--deal" => options.deal_path = args.next().map(PathBuf::from),
+            "--health" => options.setup.health = Some(parse_value(&arg, args.next())),
+            "--weapon" => options.setup.weapon = Some(parse_value(&arg, args.next())),
+            "--last-slain" => options.setup.last_slain = Some(parse_value(&arg, args.next())),
+            "--record" => options.record_path = args.next().map(PathBuf::from),
+            "replay" => options.replay_path = args.next().map(PathBuf::from),
+            "--verify" => options.verify = true,
+            "solve" => options.solve = true,
+            _ => {}
         }
     }
 
-    if let Some(outcome) = game.outcome() {
-        Printer::print_outcome(outcome);
+    if !options.setup.is_empty() && options.deal_path.is_none() {
+        eprintln!("--health, --weapon and --last-slain only work with --deal.");
+        std::process::exit(1);
+    }
+
+    options
+}
+
+// Exits when the flag has no value or one that does not parse.
+fn parse_value<T: FromStr>(flag: &str, value: Option<String>) -> T {
+    match value.as_deref().map(str::parse) {
+        Some(Ok(value)) => value,
+        Some(Err(_)) => {
+            eprintln!("Invalid value for {}: {}", flag, value.unwrap_or_default());
+            std::process::exit(1);
+        }
+        None => {
+            eprintln!("Missing value for {}", flag);
+            std::process::exit(1);
+        }
     }
 }
@@ -0,0 +1,17 @@
+use alloc::vec::Vec;
+
+use crate::{
+    action::Action,
+    error::GameError,
+    game::{GameEvent, GameInfo, GameOutcome},
+};
+
+// Every method has an empty default, so observers only implement the hooks
+// they care about.
+pub trait GameObserver {
+    fn on_turn_start(&mut self, _info: &GameInfo) {}
+
+    fn on_action(&mut self, _action: &Action, _result: &Result<Vec<GameEvent>, GameError>) {}
+
+    fn on_game_end(&mut self, _outcome: &GameOutcome) {}
+}
@@ -1,91 +1,150 @@
 use crate::{
     card::{Card, CardKind},
     error::GameError,
+    rules::RuleSet,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Weapon {
+    pub card: Option<Card>,
     pub strength: u8,
     pub last_slain_monster_strength: u8,
 }
 
-#[derive(Debug)]
+// Which monsters the equipped weapon can still slay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeaponReach {
+    Unarmed,
+    Any,
+    UpTo(u8),
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Player {
     pub health: u8,
     pub weapon: Weapon,
     pub last_healed_turn: u8,
+    pub potions_this_turn: u8,
 }
 
 impl Player {
-    pub fn new() -> Self {
+    pub fn new(rules: &RuleSet) -> Self {
         return Self {
-            health: 20,
+            health: rules.starting_health,
             weapon: Weapon {
+                card: None,
                 strength: 0,
                 last_slain_monster_strength: 0,
             },
             last_healed_turn: 0,
+            potions_this_turn: 0,
         };
     }
 
-    pub fn fight(&mut self, card: &Card) -> Result<(), GameError> {
+    pub fn fight(&mut self, card: &Card) -> Result<u8, GameError> {
         if !matches!(card.kind, CardKind::Monster) {
-            return Err(GameError::NotAMonster);
+            return Err(GameError::NotAMonster(card.clone()));
         }
 
         let damage = card.strength.min(self.health);
         self.health -= damage;
 
-        Ok(())
+        Ok(damage)
     }
 
-    pub fn kill(&mut self, card: &Card) -> Result<(), GameError> {
+    pub fn can_kill(&self, card: &Card, rules: &RuleSet) -> Result<(), GameError> {
         if !matches!(card.kind, CardKind::Monster) {
-            return Err(GameError::NotAMonster);
+            return Err(GameError::NotAMonster(card.clone()));
         }
 
-        if self.weapon.strength == 0 {
-            return Err(GameError::NoWeaponEquipped);
+        match self.reach(rules) {
+            WeaponReach::Unarmed => Err(GameError::NoWeaponEquipped),
+            WeaponReach::UpTo(strongest) if card.strength > strongest => {
+                Err(GameError::MonsterTooStrongForWeapon {
+                    monster: card.clone(),
+                    last_slain: self.weapon.last_slain_monster_strength,
+                    strongest,
+                })
+            }
+            _ => Ok(()),
         }
+    }
 
-        if self.weapon.last_slain_monster_strength != 0
-            && card.strength >= self.weapon.last_slain_monster_strength
-        {
-            return Err(GameError::MonsterTooStrongForWeapon);
+    pub fn reach(&self, rules: &RuleSet) -> WeaponReach {
+        if self.weapon.strength == 0 {
+            return WeaponReach::Unarmed;
         }
 
-        if card.strength > self.weapon.strength {
-            self.health -= (card.strength - self.weapon.strength).min(self.health);
+        let limit = self.weapon.last_slain_monster_strength;
+
+        if limit == 0 {
+            WeaponReach::Any
+        } else if rules.slay_equal_strength {
+            WeaponReach::UpTo(limit)
+        } else {
+            WeaponReach::UpTo(limit - 1)
         }
+    }
+
+    pub fn kill(&mut self, card: &Card, rules: &RuleSet) -> Result<u8, GameError> {
+        self.can_kill(card, rules)?;
+
+        let damage = card
+            .strength
+            .saturating_sub(self.weapon.strength)
+            .min(self.health);
+        self.health -= damage;
 
         self.weapon.last_slain_monster_strength = card.strength;
 
-        Ok(())
+        Ok(damage)
     }
 
-    pub fn equip_weapon(&mut self, card: &Card) -> Result<(), GameError> {
+    pub fn equip_weapon(&mut self, card: &Card) -> Result<Option<Card>, GameError> {
         if !matches!(card.kind, CardKind::Weapon) {
-            return Err(GameError::NotAWeapon);
+            return Err(GameError::NotAWeapon(card.clone()));
         }
 
-        self.weapon = Weapon {
-            strength: card.strength,
-            last_slain_monster_strength: 0,
-        };
+        let previous = core::mem::replace(
+            &mut self.weapon,
+            Weapon {
+                card: Some(card.clone()),
+                strength: card.strength,
+                last_slain_monster_strength: 0,
+            },
+        );
 
-        Ok(())
+        Ok(previous.card)
     }
 
-    pub fn heal(&mut self, card: &Card, turn: u8) -> Result<(), GameError> {
+    pub fn heal(&mut self, card: &Card, turn: u8, rules: &RuleSet) -> Result<bool, GameError> {
         if !matches!(card.kind, CardKind::Potion) {
-            return Err(GameError::NotAPotion);
+            return Err(GameError::NotAPotion(card.clone()));
         }
 
         if self.last_healed_turn != turn {
-            self.health = (self.health + card.strength).min(20);
-            self.last_healed_turn = turn;
+            self.potions_this_turn = 0;
+        }
+
+        if self.potions_this_turn >= rules.potions_per_turn {
+            return Ok(false);
         }
 
-        Ok(())
+        self.health = self
+            .health
+            .saturating_add(card.strength)
+            .min(rules.max_health);
+        self.last_healed_turn = turn;
+        self.potions_this_turn += 1;
+
+        Ok(true)
+    }
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        return Self::new(&RuleSet::default());
     }
 }
@@ -0,0 +1,256 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, str::FromStr};
+#[cfg(feature = "std")]
+use std::{fs, path::Path};
+
+#[cfg(feature = "rand-seeded")]
+use crate::deck::Deck;
+use crate::{
+    action::Action,
+    card::Card,
+    error::{DealError, ReplayError},
+    game::{DealSetup, Game, GameInfo},
+    rules::{RULES_VERSION, RuleSet},
+};
+
+const HEADER: &str = "scoundrel-replay 1";
+
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub seed: Option<u64>,
+    pub rules_version: u32,
+    pub rules: RuleSet,
+    pub deck_spec: Option<String>,
+    // An explicit card order, used instead of the seed for dealt puzzles.
+    pub deal: Option<Vec<Card>>,
+    pub setup: Option<DealSetup>,
+    pub actions: Vec<Action>,
+    pub score: Option<i16>,
+}
+
+impl Replay {
+    pub fn from_game(game: &Game) -> Self {
+        return Self {
+            seed: game.seed(),
+            rules_version: RULES_VERSION,
+            rules: *game.rules(),
+            deck_spec: game.deck_spec().map(str::to_string),
+            deal: game.dealt().map(<[Card]>::to_vec),
+            setup: game.setup().cloned(),
+            actions: game.actions().to_vec(),
+            score: game.outcome().map(|outcome| outcome.score),
+        };
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, ReplayError> {
+        let mut lines = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty());
+
+        match lines.next() {
+            Some((_, line)) if line.trim() == HEADER => {}
+            Some((index, _)) => return Err(ReplayError::Malformed { line: index + 1 }),
+            None => return Err(ReplayError::Malformed { line: 1 }),
+        }
+
+        let mut seed = None;
+        let mut rules_version = None;
+        let mut rules = RuleSet::official();
+        let mut deck_spec = None;
+        let mut deal = None;
+        let mut setup = None;
+        let mut actions = Vec::new();
+        let mut score = None;
+
+        for (index, line) in lines {
+            let malformed = ReplayError::Malformed { line: index + 1 };
+            let mut parts = line.split_whitespace();
+
+            match parts.next() {
+                Some("seed") => seed = Some(Self::parse_value(parts.next(), malformed)?),
+                Some("rules") => rules_version = Some(Self::parse_value(parts.next(), malformed)?),
+                Some("ruleset") => {
+                    let pairs: Vec<&str> = parts.collect();
+                    rules = RuleSet::parse(&pairs.join(" ")).map_err(ReplayError::Rules)?;
+                }
+                Some("deck") => deck_spec = Some(parts.collect::<Vec<_>>().join(" ")),
+                Some("deal") => {
+                    let cards = parts.map(str::parse).collect::<Result<Vec<Card>, _>>();
+                    deal = Some(cards.map_err(|e| ReplayError::Deal(DealError::Card(e)))?);
+                }
+                Some("setup") => {
+                    let pairs: Vec<&str> = parts.collect();
+                    setup = Some(DealSetup::parse(&pairs.join(" ")).map_err(ReplayError::Deal)?);
+                }
+                Some("score") => score = Some(Self::parse_value(parts.next(), malformed)?),
+                Some("actions") => {
+                    for notation in parts {
+                        actions.push(Self::parse_action(notation)?);
+                    }
+                }
+                _ => return Err(malformed),
+            }
+        }
+
+        let line = contents.lines().count();
+
+        if seed.is_none() && deal.is_none() {
+            return Err(ReplayError::Malformed { line });
+        }
+
+        return Ok(Self {
+            seed,
+            rules_version: rules_version.ok_or(ReplayError::Malformed { line })?,
+            rules,
+            deck_spec,
+            deal,
+            setup,
+            actions,
+            score,
+        });
+    }
+
+    #[cfg(feature = "std")]
+    pub fn read(path: &Path) -> Result<Self, ReplayError> {
+        let contents = fs::read_to_string(path).map_err(ReplayError::Io)?;
+
+        return Self::parse(&contents);
+    }
+
+    #[cfg(feature = "std")]
+    pub fn write(&self, path: &Path) -> Result<(), ReplayError> {
+        return fs::write(path, self.to_string()).map_err(ReplayError::Io);
+    }
+
+    // Re-runs the replay and returns the table after the initial deal and after
+    // every recorded action, for stepping back and forth.
+    pub fn frames(&self) -> Result<Vec<GameInfo>, ReplayError> {
+        let mut frames = Vec::new();
+
+        self.simulate(|game| frames.push(game.game_info()))?;
+
+        return Ok(frames);
+    }
+
+    pub fn verify(&self) -> Result<i16, ReplayError> {
+        let game = self.simulate(|_| {})?;
+        let outcome = game.outcome().ok_or(ReplayError::Unfinished)?;
+        let actual = outcome.score;
+        let claimed = self.score.ok_or(ReplayError::MissingScore)?;
+
+        if claimed != actual {
+            return Err(ReplayError::ScoreMismatch { claimed, actual });
+        }
+
+        return Ok(actual);
+    }
+
+    fn simulate(&self, mut on_step: impl FnMut(&Game)) -> Result<Game, ReplayError> {
+        let mut rules = self.rules;
+
+        match self.rules_version {
+            RULES_VERSION => {}
+
+            // Version 1 ended the game as soon as the deck ran out.
+            1 => rules.leave_last_room = true,
+
+            other => return Err(ReplayError::UnsupportedRules(other)),
+        }
+
+        let mut game = match (&self.deal, self.seed) {
+            (Some(cards), _) => {
+                let setup = self.setup.clone().unwrap_or_default();
+                Game::from_cards(cards.clone(), setup, rules).map_err(ReplayError::Deal)?
+            }
+            #[cfg(feature = "rand-seeded")]
+            (None, Some(seed)) => {
+                let deck = match &self.deck_spec {
+                    Some(spec) => Deck::from_spec(spec, seed).map_err(ReplayError::DeckSpec)?,
+                    None => Deck::with_seed(seed),
+                };
+                Game::from_deck(deck, rules).map_err(ReplayError::Rules)?
+            }
+            #[cfg(not(feature = "rand-seeded"))]
+            (None, Some(_)) => return Err(ReplayError::SeedUnsupported),
+            (None, None) => return Err(ReplayError::Malformed { line: 1 }),
+        };
+        game.set_undo_limit(Some(0));
+        game.start_turn();
+        on_step(&game);
+
+        for (step, action) in self.actions.iter().enumerate() {
+            game.apply(*action)
+                .map_err(|error| ReplayError::IllegalAction {
+                    step: step + 1,
+                    error,
+                })?;
+
+            on_step(&game);
+        }
+
+        return Ok(game);
+    }
+
+    fn parse_value<T: FromStr>(value: Option<&str>, error: ReplayError) -> Result<T, ReplayError> {
+        return value.and_then(|v| v.parse().ok()).ok_or(error);
+    }
+
+    fn parse_action(notation: &str) -> Result<Action, ReplayError> {
+        let unknown = || ReplayError::UnknownAction(notation.to_string());
+
+        if notation == "s" {
+            return Ok(Action::Skip);
+        }
+
+        let (command, position) = notation.split_at_checked(1).ok_or_else(unknown)?;
+        let index = match position.parse::<usize>() {
+            Ok(position) if position > 0 => position - 1,
+            _ => return Err(unknown()),
+        };
+
+        match command {
+            "f" => Ok(Action::Fight { index }),
+            "a" => Ok(Action::Kill { index }),
+            "e" => Ok(Action::Equip { index }),
+            "h" => Ok(Action::Heal { index }),
+            _ => Err(unknown()),
+        }
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "seed {seed}")?;
+        }
+
+        writeln!(f, "rules {}", self.rules_version)?;
+        writeln!(f, "ruleset {}", self.rules)?;
+
+        if let Some(spec) = &self.deck_spec {
+            writeln!(f, "deck {spec}")?;
+        }
+
+        if let Some(cards) = &self.deal {
+            let cards: Vec<String> = cards.iter().map(Card::to_string).collect();
+            writeln!(f, "deal {}", cards.join(" "))?;
+        }
+
+        if let Some(setup) = &self.setup {
+            writeln!(f, "setup {setup}")?;
+        }
+
+        if let Some(score) = self.score {
+            writeln!(f, "score {score}")?;
+        }
+
+        let actions: Vec<String> = self.actions.iter().filter_map(Action::notation).collect();
+
+        writeln!(f, "actions {}", actions.join(" "))
+    }
+}
@@ -1,15 +1,24 @@
-use crate::{card::Card, error::GameError};
+use alloc::vec::Vec;
 
+use crate::{
+    card::{Card, CardKind},
+    deck::Deck,
+    error::GameError,
+    rules::RuleSet,
+};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Room {
     cards: Vec<Card>,
     max_size: usize,
 }
 
 impl Room {
-    pub fn new() -> Self {
+    pub fn new(rules: &RuleSet) -> Self {
         return Self {
             cards: Vec::new(),
-            max_size: 4,
+            max_size: rules.room_size,
         };
     }
 
@@ -21,13 +30,23 @@ impl Room {
         return self.cards.len();
     }
 
+    pub fn is_empty(&self) -> bool {
+        return self.cards.is_empty();
+    }
+
     pub fn get(&self, index: usize) -> Result<&Card, GameError> {
-        self.cards.get(index).ok_or(GameError::IndexOutOfBounds)
+        self.cards.get(index).ok_or(GameError::IndexOutOfBounds {
+            index,
+            len: self.cards.len(),
+        })
     }
 
     pub fn remove(&mut self, index: usize) -> Result<Card, GameError> {
         if index >= self.cards.len() {
-            return Err(GameError::IndexOutOfBounds);
+            return Err(GameError::IndexOutOfBounds {
+                index,
+                len: self.cards.len(),
+            });
         }
 
         Ok(self.cards.remove(index))
@@ -42,8 +61,17 @@ impl Room {
         Ok(())
     }
 
-    pub fn clear_into(&mut self, deck: &mut Vec<Card>) {
-        deck.splice(0..0, self.cards.drain(..));
+    pub fn clear_into(&mut self, deck: &mut Deck) {
+        deck.put_all_on_bottom(self.cards.drain(..).collect());
+    }
+
+    pub fn remaining_monster_strength(&self) -> u16 {
+        return self
+            .cards
+            .iter()
+            .filter(|card| matches!(card.kind, CardKind::Monster))
+            .map(|card| card.strength as u16)
+            .sum();
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &Card> {
@@ -54,3 +82,9 @@ impl Room {
         return &self.cards;
     }
 }
+
+impl Default for Room {
+    fn default() -> Self {
+        return Self::new(&RuleSet::default());
+    }
+}
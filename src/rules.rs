@@ -0,0 +1,125 @@
+use alloc::{format, string::ToString};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::{fs, path::Path};
+
+use crate::error::RulesError;
+
+pub const RULES_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RuleSet {
+    pub starting_health: u8,
+    pub max_health: u8,
+    pub room_size: usize,
+    pub cards_to_face: usize,
+    pub potions_per_turn: u8,
+    pub consecutive_skips: bool,
+    pub slay_equal_strength: bool,
+    pub leave_last_room: bool,
+}
+
+impl RuleSet {
+    pub fn official() -> Self {
+        return Self {
+            starting_health: 20,
+            max_health: 20,
+            room_size: 4,
+            cards_to_face: 3,
+            potions_per_turn: 1,
+            consecutive_skips: false,
+            slay_equal_strength: false,
+            leave_last_room: false,
+        };
+    }
+
+    // Reads whitespace or newline separated `name=value` pairs on top of the
+    // official rules. Anything after `#` on a line is ignored.
+    pub fn parse(text: &str) -> Result<Self, RulesError> {
+        let mut rules = Self::official();
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("");
+
+            for pair in line.split_whitespace() {
+                let (name, value) = pair
+                    .split_once('=')
+                    .ok_or_else(|| RulesError::InvalidValue(pair.to_string()))?;
+
+                rules.set(name, value)?;
+            }
+        }
+
+        rules.validate()?;
+
+        return Ok(rules);
+    }
+
+    #[cfg(feature = "std")]
+    pub fn load(path: &Path) -> Result<Self, RulesError> {
+        let text = fs::read_to_string(path).map_err(RulesError::Io)?;
+
+        return Self::parse(&text);
+    }
+
+    pub fn validate(&self) -> Result<(), RulesError> {
+        if self.starting_health == 0 || self.starting_health > self.max_health {
+            return Err(RulesError::InvalidHealth);
+        }
+
+        if self.cards_to_face == 0 || self.cards_to_face > self.room_size {
+            return Err(RulesError::InvalidRoom);
+        }
+
+        Ok(())
+    }
+
+    // The number of cards left in the room once a turn is complete.
+    pub fn cards_carried_over(&self) -> usize {
+        return self.room_size - self.cards_to_face;
+    }
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), RulesError> {
+        let invalid = || RulesError::InvalidValue(format!("{name}={value}"));
+
+        match name {
+            "starting_health" => self.starting_health = value.parse().map_err(|_| invalid())?,
+            "max_health" => self.max_health = value.parse().map_err(|_| invalid())?,
+            "room_size" => self.room_size = value.parse().map_err(|_| invalid())?,
+            "cards_to_face" => self.cards_to_face = value.parse().map_err(|_| invalid())?,
+            "potions_per_turn" => self.potions_per_turn = value.parse().map_err(|_| invalid())?,
+            "consecutive_skips" => self.consecutive_skips = value.parse().map_err(|_| invalid())?,
+            "slay_equal_strength" => {
+                self.slay_equal_strength = value.parse().map_err(|_| invalid())?
+            }
+            "leave_last_room" => self.leave_last_room = value.parse().map_err(|_| invalid())?,
+            _ => return Err(RulesError::UnknownRule(name.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        return Self::official();
+    }
+}
+
+impl fmt::Display for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "starting_health={} max_health={} room_size={} cards_to_face={} potions_per_turn={} consecutive_skips={} slay_equal_strength={} leave_last_room={}",
+            self.starting_health,
+            self.max_health,
+            self.room_size,
+            self.cards_to_face,
+            self.potions_per_turn,
+            self.consecutive_skips,
+            self.slay_equal_strength,
+            self.leave_last_room,
+        )
+    }
+}
@@ -0,0 +1,125 @@
+use std::{fs, path::Path};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{error::SaveError, game::Game, rules::RuleSet};
+
+pub const SAVE_VERSION: u32 = 8;
+
+#[derive(Serialize)]
+struct SaveFile<'a> {
+    version: u32,
+    game: &'a Game,
+}
+
+pub fn to_string(game: &Game) -> Result<String, SaveError> {
+    let file = SaveFile {
+        version: SAVE_VERSION,
+        game,
+    };
+
+    return serde_json::to_string_pretty(&file).map_err(SaveError::Malformed);
+}
+
+pub fn from_str(contents: &str) -> Result<Game, SaveError> {
+    let mut file: Value = serde_json::from_str(contents).map_err(SaveError::Malformed)?;
+
+    let version = file
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or(SaveError::MissingVersion)? as u32;
+
+    let game = migrate(version, file["game"].take())?;
+
+    return serde_json::from_value(game).map_err(SaveError::Malformed);
+}
+
+pub fn write(game: &Game, path: &Path) -> Result<(), SaveError> {
+    let contents = to_string(game)?;
+
+    return fs::write(path, contents).map_err(SaveError::Io);
+}
+
+pub fn read(path: &Path) -> Result<Game, SaveError> {
+    let contents = fs::read_to_string(path).map_err(SaveError::Io)?;
+
+    return from_str(&contents);
+}
+
+// Each format change bumps SAVE_VERSION and adds a step here that upgrades
+// the previous version's `game` value.
+fn migrate(version: u32, mut game: Value) -> Result<Value, SaveError> {
+    match version {
+        SAVE_VERSION => Ok(game),
+
+        // Version 7 only knew shuffled decks.
+        7 => {
+            game["deck"]["dealt"] = Value::Null;
+            game["setup"] = Value::Null;
+            migrate(8, game)
+        }
+
+        // Version 6 did not track which bottom cards had been seen.
+        6 => {
+            game["deck"]["known_bottom"] = Value::from(0);
+            migrate(7, game)
+        }
+
+        // Version 5 only knew the standard deck.
+        5 => {
+            game["deck"]["spec"] = Value::Null;
+            migrate(6, game)
+        }
+
+        // Version 4 did not remember the last resolved card.
+        4 => {
+            game["last_resolved"] = Value::Null;
+            migrate(5, game)
+        }
+
+        // Version 3 games ended as soon as the deck ran out.
+        3 => {
+            game["rules"]["leave_last_room"] = Value::Bool(true);
+            migrate(4, game)
+        }
+
+        // Version 2 predates house rules, so those games used the official ones.
+        2 => {
+            let rules = serde_json::to_value(RuleSet::official()).map_err(SaveError::Malformed)?;
+            let healed_this_turn = game["player"]["last_healed_turn"] == game["turn"];
+
+            game["rules"] = rules;
+            game["player"]["potions_this_turn"] = Value::from(u8::from(healed_this_turn));
+            migrate(3, game)
+        }
+
+        // Version 1 did not record the applied actions.
+        1 => {
+            game["actions"] = Value::Array(Vec::new());
+            migrate(2, game)
+        }
+
+        other => Err(SaveError::UnsupportedVersion(other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Saved by the first release after a skip on seed 1, with the deck cut
+    // down to two cards.
+    const VERSION_1: &str = r#"{"version":1,"game":{"deck":{"cards":[{"suit":"Spades","rank":{"Num":3},"strength":3,"kind":"Monster"},{"suit":"Clubs","rank":{"Num":10},"strength":10,"kind":"Monster"}],"seed":1},"room":{"cards":[{"suit":"Clubs","rank":"Jack","strength":11,"kind":"Monster"},{"suit":"Diamonds","rank":{"Num":4},"strength":4,"kind":"Weapon"},{"suit":"Spades","rank":{"Num":2},"strength":2,"kind":"Monster"},{"suit":"Clubs","rank":{"Num":5},"strength":5,"kind":"Monster"}],"max_size":4},"player":{"health":20,"weapon":{"card":null,"strength":0,"last_slain_monster_strength":0},"last_healed_turn":0},"discard":{"cards":[]},"turn":2,"last_skipped_turn":1,"undo_limit":null}}"#;
+
+    #[test]
+    fn migrates_version_1() {
+        let game = from_str(VERSION_1).unwrap();
+
+        assert!(game.rules().leave_last_room);
+        assert!(game.actions().is_empty());
+        assert!(game.observation().known_bottom.is_empty());
+        assert_eq!(game.game_info().turn, 2);
+        assert_eq!(game.observation().room.len(), 4);
+    }
+}
@@ -0,0 +1,702 @@
+use std::collections::HashMap;
+
+use crate::{
+    action::Action,
+    card::{Card, CardKind},
+    game::{Ending, Game, Phase, Position},
+    rules::RuleSet,
+};
+
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub score: i16,
+    pub ending: Ending,
+    // The moves from the given game to the best ending.
+    pub line: Vec<Action>,
+    // How many distinct turns were searched.
+    pub positions: usize,
+}
+
+impl Solution {
+    pub fn is_win(&self) -> bool {
+        return self.ending != Ending::Died;
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Entry {
+    // `moves` are the actions of the best way to play the turn.
+    Exact { score: i16, moves: Vec<Action> },
+    // The search was cut short, the turn scores no more than this.
+    AtMost(i16),
+}
+
+// Cards are packed as `kind * 16 + strength`, so cards that only differ in
+// suit are the same card to the search.
+type Packed = u8;
+
+const MONSTER: u8 = 0;
+const WEAPON: u8 = 1;
+const POTION: u8 = 2;
+
+// A position at the start of a turn, or part way through one for the game the
+// solver is handed. The health is kept apart, as turns that only differ in
+// health are compared against each other.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Turn {
+    // Bottom card first, the next card drawn is the last one.
+    deck: Vec<Packed>,
+    room: Vec<Packed>,
+    weapon: u8,
+    last_slain: u8,
+    potions: u8,
+    can_skip: bool,
+}
+
+// The room and player while a turn is played out.
+struct Play {
+    room: Vec<Packed>,
+    health: u8,
+    weapon: u8,
+    last_slain: u8,
+    potions: u8,
+    last_resolved: Packed,
+}
+
+enum Step {
+    Finished(i16),
+    Next(Turn, u8),
+}
+
+// The distinct ways a turn can end, with the moves that lead there. Of two
+// endings that only differ in health the healthier one is kept, it can follow
+// any line the other can.
+#[derive(Default)]
+struct Endings {
+    finished: Option<(i16, Vec<Action>)>,
+    next: HashMap<Turn, (u8, Vec<Action>)>,
+}
+
+// Searches every line of a game whose deck order is known.
+//
+// The search plays whole turns on a compact copy of the rules and remembers
+// every turn it has solved. Skips put the room under the deck in order, just
+// like `Room::clear_into`, so a skipped room comes back exactly when it would
+// in play. The best line is then played on a copy of the real game, which
+// gives the reported score.
+pub struct Solver {
+    rules: RuleSet,
+    // An entry per health each turn was searched with.
+    memo: HashMap<Turn, Vec<(u8, Entry)>>,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        return Self {
+            rules: RuleSet::official(),
+            memo: HashMap::new(),
+        };
+    }
+
+    pub fn solve(&mut self, game: &Game) -> Solution {
+        if *game.rules() != self.rules {
+            self.rules = *game.rules();
+            self.memo.clear();
+        }
+
+        let mut game = game.branch();
+
+        if game.phase() == Phase::TurnComplete {
+            game.start_turn();
+        }
+
+        let mut line = Vec::new();
+
+        while !game.is_over() {
+            let (turn, health) = self.turn(&game);
+
+            // Asking whether the best imaginable score can be reached prunes
+            // far more than searching with an open window. Each failed try
+            // asks for less, twice as much less as the try before.
+            let mut bound = self.upper_bound(&turn, health);
+            let mut step = 1;
+
+            while !matches!(self.entry(&turn, health), Some(Entry::Exact { .. })) {
+                bound = self.search(&turn, health, bound.saturating_sub(step));
+                step = step.saturating_mul(2);
+            }
+
+            let Some(Entry::Exact { moves, .. }) = self.entry(&turn, health) else {
+                unreachable!("the loop above only stops on an exact entry");
+            };
+
+            for action in moves.clone() {
+                if game.apply(action).is_err() {
+                    unreachable!("the search only plays legal moves");
+                }
+                line.push(action);
+            }
+        }
+
+        let Some(outcome) = game.outcome() else {
+            unreachable!("the line is played until the game is over");
+        };
+
+        return Solution {
+            score: outcome.score,
+            ending: outcome.ending,
+            line,
+            positions: self.memo.values().map(Vec::len).sum(),
+        };
+    }
+
+    fn turn(&self, game: &Game) -> (Turn, u8) {
+        let Position {
+            deck,
+            room,
+            health,
+            weapon_strength,
+            last_slain,
+            potions_this_turn,
+            skipped_last_turn,
+        } = game.position();
+
+        let turn = Turn {
+            can_skip: game.phase() == Phase::AwaitingRoomDecision
+                && !deck.is_empty()
+                && (self.rules.consecutive_skips || !skipped_last_turn),
+            deck: deck.iter().map(Self::pack).collect(),
+            room: room.iter().map(Self::pack).collect(),
+            weapon: weapon_strength,
+            last_slain,
+            potions: potions_this_turn,
+        };
+
+        return (turn, health);
+    }
+
+    // Returns the best score reachable from `turn`. Anything at or below
+    // `alpha` is already beaten elsewhere, so such results are only upper
+    // bounds.
+    fn search(&mut self, turn: &Turn, health: u8, alpha: i16) -> i16 {
+        let mut bound = self.upper_bound(turn, health);
+
+        // More health never hurts, so whatever bounds the same turn with at
+        // least as much health bounds this one too.
+        for (known, entry) in self.memo.get(turn).into_iter().flatten() {
+            match entry {
+                Entry::Exact { score, .. } if *known == health => return *score,
+                Entry::Exact { score, .. } | Entry::AtMost(score) if *known >= health => {
+                    bound = bound.min(*score);
+                }
+                _ => {}
+            }
+        }
+
+        if bound <= alpha {
+            return bound;
+        }
+
+        let mut best = i16::MIN;
+        let mut best_moves = Vec::new();
+
+        for (step, moves) in self.endings(turn, health) {
+            let score = match step {
+                Step::Finished(score) => score,
+                Step::Next(next, health) => self.search(&next, health, alpha.max(best)),
+            };
+
+            if score > best {
+                best = score;
+                best_moves = moves;
+            }
+
+            if best >= bound {
+                break;
+            }
+        }
+
+        let entry = if best > alpha {
+            Entry::Exact {
+                score: best,
+                moves: best_moves,
+            }
+        } else {
+            Entry::AtMost(best)
+        };
+
+        match self.memo.get_mut(turn) {
+            Some(entries) => match entries.iter_mut().find(|(known, _)| *known == health) {
+                Some((_, kept)) => *kept = entry,
+                None => entries.push((health, entry)),
+            },
+            None => {
+                self.memo.insert(turn.clone(), vec![(health, entry)]);
+            }
+        }
+
+        return best;
+    }
+
+    fn entry(&self, turn: &Turn, health: u8) -> Option<&Entry> {
+        return self
+            .memo
+            .get(turn)?
+            .iter()
+            .find(|(known, _)| *known == health)
+            .map(|(_, entry)| entry);
+    }
+
+    // Every distinct way to play out the turn, the healthiest first so the
+    // rest can be pruned.
+    fn endings(&self, turn: &Turn, health: u8) -> Vec<(Step, Vec<Action>)> {
+        let mut endings = Endings::default();
+        let mut room = turn.clone();
+        let mut moves = Vec::new();
+
+        // A skipped room is played straight after the skip, so skips never
+        // show up as turns of their own. Under consecutive skips the rooms
+        // are skipped until the dungeon comes back around to where it started.
+        loop {
+            let mut play = Play {
+                room: room.room.clone(),
+                health,
+                weapon: room.weapon,
+                last_slain: room.last_slain,
+                potions: room.potions,
+                last_resolved: MONSTER,
+            };
+
+            self.expand(&room, &mut play, &mut moves, &mut endings);
+
+            if !room.can_skip {
+                break;
+            }
+
+            let mut deck = room.room.clone();
+            deck.extend_from_slice(&room.deck);
+
+            let skipped = Turn {
+                deck,
+                room: Vec::new(),
+                potions: 0,
+                can_skip: self.rules.consecutive_skips,
+                ..room
+            };
+
+            // The skipped room is still in the deck, so the deal never ends
+            // the game here.
+            let Step::Next(next, _) = self.deal(skipped, health, MONSTER) else {
+                break;
+            };
+
+            if next.deck == turn.deck && next.room == turn.room {
+                break;
+            }
+
+            room = next;
+            moves.push(Action::Skip);
+        }
+
+        let mut steps: Vec<(i16, Step, Vec<Action>)> = Vec::new();
+
+        if let Some((score, moves)) = endings.finished {
+            steps.push((score, Step::Finished(score), moves));
+        }
+
+        for (next, (health, moves)) in endings.next {
+            steps.push((health as i16, Step::Next(next, health), moves));
+        }
+
+        steps.sort_by_key(|(estimate, ..)| core::cmp::Reverse(*estimate));
+
+        return steps
+            .into_iter()
+            .map(|(_, step, moves)| (step, moves))
+            .collect();
+    }
+
+    // Tries every card of the room in turn, the same way `Game::resolve` would
+    // play it, until the turn ends or the player dies.
+    fn expand(&self, turn: &Turn, play: &mut Play, moves: &mut Vec<Action>, out: &mut Endings) {
+        for index in 0..play.room.len() {
+            let card = play.room[index];
+            let strength = card % 16;
+
+            let actions: &[Action] = match card / 16 {
+                MONSTER if self.can_kill(play, strength) => {
+                    &[Action::Kill { index }, Action::Fight { index }]
+                }
+                MONSTER => &[Action::Fight { index }],
+                WEAPON => &[Action::Equip { index }],
+                _ => &[Action::Heal { index }],
+            };
+
+            for action in actions {
+                let before = (play.health, play.weapon, play.last_slain, play.potions);
+                let last_resolved = play.last_resolved;
+
+                match action {
+                    Action::Fight { .. } => play.health -= strength.min(play.health),
+                    Action::Kill { .. } => {
+                        play.health -= strength.saturating_sub(play.weapon).min(play.health);
+                        play.last_slain = strength;
+                    }
+                    Action::Equip { .. } => {
+                        play.weapon = strength;
+                        play.last_slain = 0;
+                    }
+                    _ => {
+                        if play.potions < self.rules.potions_per_turn {
+                            play.health = play
+                                .health
+                                .saturating_add(strength)
+                                .min(self.rules.max_health);
+                            play.potions += 1;
+                        }
+                    }
+                }
+
+                play.room.remove(index);
+                play.last_resolved = card;
+                moves.push(*action);
+
+                let turn_ends = if turn.deck.is_empty() {
+                    play.room.is_empty()
+                } else {
+                    play.room.len() == self.rules.cards_carried_over()
+                };
+
+                if play.health == 0 {
+                    let penalty =
+                        Self::monster_strength(&turn.deck) + Self::monster_strength(&play.room);
+                    out.add(Step::Finished(-penalty), moves);
+                } else if turn_ends {
+                    let next = Turn {
+                        deck: turn.deck.clone(),
+                        room: play.room.clone(),
+                        weapon: play.weapon,
+                        last_slain: play.last_slain,
+                        potions: 0,
+                        can_skip: true,
+                    };
+                    out.add(self.deal(next, play.health, play.last_resolved), moves);
+                } else {
+                    self.expand(turn, play, moves, out);
+                }
+
+                moves.pop();
+                play.room.insert(index, card);
+                (play.health, play.weapon, play.last_slain, play.potions) = before;
+                play.last_resolved = last_resolved;
+            }
+        }
+    }
+
+    // Fills the room for the next turn and checks whether that ends the game,
+    // like `Game::ending` does.
+    fn deal(&self, mut next: Turn, health: u8, last_resolved: Packed) -> Step {
+        while next.room.len() < self.rules.room_size {
+            match next.deck.pop() {
+                Some(card) => next.room.push(card),
+                None => break,
+            }
+        }
+
+        if !next.deck.is_empty() {
+            next.can_skip &= next.room.len() == self.rules.room_size;
+            return Step::Next(next, health);
+        }
+
+        if next.room.is_empty() || self.rules.leave_last_room {
+            let bonus = if last_resolved / 16 == POTION && health == self.rules.max_health {
+                last_resolved % 16
+            } else {
+                0
+            };
+
+            return Step::Finished(health as i16 + bonus as i16);
+        }
+
+        next.can_skip = false;
+        return Step::Next(next, health);
+    }
+
+    // Mirrors `Player::reach`.
+    fn can_kill(&self, play: &Play, strength: u8) -> bool {
+        if play.weapon == 0 {
+            return false;
+        }
+
+        match play.last_slain {
+            0 => true,
+            limit if self.rules.slay_equal_strength => strength <= limit,
+            limit => strength < limit,
+        }
+    }
+
+    // Surviving can at best drink every potion left and take only the damage
+    // the strongest weapon left cannot absorb. When the last room may be left,
+    // the monsters in it never strike, so the worst of them are not counted.
+    // The bonus needs full health at the end, and dying never scores above
+    // zero.
+    fn upper_bound(&self, turn: &Turn, health: u8) -> i16 {
+        let strengths = |kind: u8| {
+            turn.deck
+                .iter()
+                .chain(&turn.room)
+                .filter(move |card| *card / 16 == kind)
+                .map(|card| (card % 16) as i16)
+        };
+
+        let weapon = strengths(WEAPON).fold(turn.weapon as i16, i16::max);
+        let mut damages: Vec<i16> = strengths(MONSTER)
+            .map(|strength| (strength - weapon).max(0))
+            .collect();
+
+        if self.rules.leave_last_room {
+            damages.sort_unstable_by(|a, b| b.cmp(a));
+            damages.drain(..self.rules.room_size.min(damages.len()));
+        }
+
+        let damage: i16 = damages.iter().sum();
+        let healing: i16 = strengths(POTION).sum();
+        let strongest = strengths(POTION).max().unwrap_or(0);
+
+        let health = health as i16 + healing - damage;
+        let max_health = self.rules.max_health as i16;
+
+        if health >= max_health {
+            return max_health + strongest;
+        }
+
+        return health.max(0);
+    }
+
+    fn monster_strength(cards: &[Packed]) -> i16 {
+        return cards
+            .iter()
+            .filter(|card| *card / 16 == MONSTER)
+            .map(|card| (card % 16) as i16)
+            .sum();
+    }
+
+    fn pack(card: &Card) -> Packed {
+        let kind = match card.kind {
+            CardKind::Monster => MONSTER,
+            CardKind::Weapon => WEAPON,
+            CardKind::Potion => POTION,
+        };
+
+        return kind * 16 + card.strength;
+    }
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl Endings {
+    fn add(&mut self, step: Step, moves: &[Action]) {
+        match step {
+            Step::Finished(score) => {
+                if self.finished.as_ref().is_none_or(|(best, _)| score > *best) {
+                    self.finished = Some((score, moves.to_vec()));
+                }
+            }
+            Step::Next(next, health) => match self.next.get_mut(&next) {
+                Some((kept, _)) if *kept >= health => {}
+                Some(kept) => *kept = (health, moves.to_vec()),
+                None => {
+                    self.next.insert(next, (health, moves.to_vec()));
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        card::{Rank, Suit},
+        game::DealSetup,
+    };
+
+    // Plays every legal line on the engine itself. A run of skips stops once
+    // it deals a room it has already dealt, it can only go around again.
+    fn brute_force(game: &Game, skipped: &mut Vec<(Vec<Card>, Vec<Card>)>) -> i16 {
+        if let Some(outcome) = game.outcome() {
+            return outcome.score;
+        }
+
+        let mut best = i16::MIN;
+
+        for action in game.legal_actions() {
+            let mut next = game.branch();
+
+            if next.apply(action).is_err() {
+                continue;
+            }
+
+            let score = if action == Action::Skip {
+                let position = next.position();
+                let dealt = (position.deck, position.room);
+
+                if skipped.contains(&dealt) {
+                    continue;
+                }
+
+                skipped.push(dealt);
+                let score = brute_force(&next, skipped);
+                skipped.pop();
+                score
+            } else {
+                brute_force(&next, &mut Vec::new())
+            };
+
+            best = best.max(score);
+        }
+
+        return best;
+    }
+
+    // The first `size` cards of a standard dungeon, shuffled with a small
+    // LCG so the deals are the same on every run.
+    fn deal(seed: u64, size: usize) -> Vec<Card> {
+        let mut cards = Vec::new();
+
+        for strength in 2..=14 {
+            let rank = Rank::from_strength(strength).unwrap();
+            cards.push(Card::new(Suit::Spades, rank));
+            cards.push(Card::new(Suit::Clubs, rank));
+
+            if strength <= 10 {
+                cards.push(Card::new(Suit::Hearts, rank));
+                cards.push(Card::new(Suit::Diamonds, rank));
+            }
+        }
+
+        let mut state = seed;
+
+        for index in (1..cards.len()).rev() {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            cards.swap(index, (state >> 33) as usize % (index + 1));
+        }
+
+        cards.truncate(size);
+        return cards;
+    }
+
+    // Deals of `size` cards, brute force soon gets slow past nine.
+    fn cross_check(rules: RuleSet, size: usize) {
+        let mut solver = Solver::new();
+
+        for seed in 0..40 {
+            let cards = deal(seed, size);
+            let game = Game::from_cards(cards.clone(), DealSetup::default(), rules).unwrap();
+
+            let mut start = game.branch();
+            start.start_turn();
+
+            let position = start.position();
+            let expected = brute_force(&start, &mut vec![(position.deck, position.room)]);
+            let solution = solver.solve(&game);
+
+            assert_eq!(solution.score, expected, "deal {cards:?}");
+        }
+    }
+
+    #[test]
+    fn matches_brute_force_official() {
+        cross_check(RuleSet::official(), 9);
+    }
+
+    #[test]
+    fn matches_brute_force_consecutive_skips() {
+        cross_check(
+            RuleSet {
+                consecutive_skips: true,
+                ..RuleSet::official()
+            },
+            8,
+        );
+    }
+
+    #[test]
+    fn matches_brute_force_slay_equal_strength() {
+        cross_check(
+            RuleSet {
+                slay_equal_strength: true,
+                ..RuleSet::official()
+            },
+            9,
+        );
+    }
+
+    #[test]
+    fn matches_brute_force_leave_last_room() {
+        cross_check(
+            RuleSet {
+                leave_last_room: true,
+                ..RuleSet::official()
+            },
+            9,
+        );
+    }
+
+    #[test]
+    fn matches_brute_force_potions_per_turn() {
+        cross_check(
+            RuleSet {
+                potions_per_turn: 2,
+                ..RuleSet::official()
+            },
+            9,
+        );
+    }
+
+    #[test]
+    fn matches_brute_force_room_size() {
+        cross_check(
+            RuleSet {
+                room_size: 3,
+                cards_to_face: 2,
+                ..RuleSet::official()
+            },
+            9,
+        );
+    }
+
+    #[test]
+    fn matches_brute_force_health() {
+        cross_check(
+            RuleSet {
+                starting_health: 8,
+                max_health: 12,
+                ..RuleSet::official()
+            },
+            9,
+        );
+    }
+
+    #[test]
+    fn leaves_the_last_room_behind() {
+        let rules = RuleSet {
+            leave_last_room: true,
+            ..RuleSet::official()
+        };
+        let game = Game::from_deal(
+            "D4 S10 S5 H10 S2 S4 SJ S9 H9 S3 SA",
+            DealSetup::default(),
+            rules,
+        )
+        .unwrap();
+
+        assert_eq!(Solver::new().solve(&game).score, 30);
+    }
+}
@@ -0,0 +1,227 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::convert::Infallible;
+
+#[cfg(feature = "rand-seeded")]
+use rand::{SeedableRng, seq::SliceRandom};
+#[cfg(feature = "rand-seeded")]
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    action::Action,
+    card::{Card, CardKind},
+    driver::{Command, InputError, InputSource},
+    error::GameError,
+    game::{Game, GameOutcome, Phase},
+    player::{Player, WeaponReach},
+    rules::RuleSet,
+    view::Observation,
+};
+
+// A bot only ever sees what the player could see. `choose` must return one of
+// `obs.legal_actions`, which is never empty while the game is running.
+pub trait Strategy {
+    fn choose(&mut self, obs: &Observation) -> Action;
+}
+
+impl<S: Strategy + ?Sized> Strategy for Box<S> {
+    fn choose(&mut self, obs: &Observation) -> Action {
+        return (**self).choose(obs);
+    }
+}
+
+// Plays the game to its end without a frontend.
+pub fn play(game: &mut Game, strategy: &mut impl Strategy) -> Result<GameOutcome, GameError> {
+    loop {
+        if let Some(outcome) = game.outcome() {
+            return Ok(outcome);
+        }
+
+        if game.phase() == Phase::TurnComplete {
+            game.start_turn();
+            continue;
+        }
+
+        let action = strategy.choose(&game.observation());
+        game.apply(action)?;
+    }
+}
+
+// Lets a bot take the player's seat in a `GameDriver`, e.g. to watch it play.
+pub struct StrategyInput<S>(pub S);
+
+impl<S: Strategy> InputSource for StrategyInput<S> {
+    type Error = Infallible;
+
+    fn next_command(&mut self, game: &Game) -> Result<Command, InputError<Self::Error>> {
+        return Ok(Command::Play(self.0.choose(&game.observation())));
+    }
+}
+
+// Plays the rest of the turn so that as little health as possible is lost.
+// Rooms that would kill it are skipped when the rules allow.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Greedy;
+
+impl Strategy for Greedy {
+    fn choose(&mut self, obs: &Observation) -> Action {
+        return choose_by(obs, |player| player.health as i32);
+    }
+}
+
+// Like `Greedy`, but also weighs what the weapon can still slay. Small
+// monsters are fought barehanded when killing them would stop the weapon from
+// reaching bigger ones later.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WeaponKeeper;
+
+impl Strategy for WeaponKeeper {
+    fn choose(&mut self, obs: &Observation) -> Action {
+        return choose_by(obs, |player| {
+            let reach = match player.reach(&obs.rules) {
+                WeaponReach::Unarmed => 0,
+                WeaponReach::Any => 14,
+                WeaponReach::UpTo(strongest) => strongest as i32,
+            };
+
+            // A weapon is worth about the damage it takes off the monsters
+            // it can still reach, count it at a third of that.
+            return player.health as i32 * 3 + player.weapon.strength as i32 * reach / 7;
+        });
+    }
+}
+
+// Picks any legal action, as a baseline the other bots should beat.
+#[cfg(feature = "rand-seeded")]
+#[derive(Debug, Clone)]
+pub struct RandomLegal {
+    rng: ChaCha8Rng,
+}
+
+#[cfg(feature = "rand-seeded")]
+impl RandomLegal {
+    pub fn new(seed: u64) -> Self {
+        return Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        };
+    }
+}
+
+#[cfg(feature = "rand-seeded")]
+impl Strategy for RandomLegal {
+    fn choose(&mut self, obs: &Observation) -> Action {
+        return *obs
+            .legal_actions
+            .choose(&mut self.rng)
+            .unwrap_or(&Action::Skip);
+    }
+}
+
+// Tries every order of the cards left to face this turn on a copy of the
+// player, and starts on the order that leaves the best `value`. A skip is
+// taken instead when every order dies.
+fn choose_by(obs: &Observation, value: impl Fn(&Player) -> i32) -> Action {
+    let to_face = if obs.deck_size == 0 {
+        obs.room.len()
+    } else {
+        obs.room
+            .len()
+            .saturating_sub(obs.rules.cards_carried_over())
+    };
+
+    let best = best_line(
+        &obs.player,
+        &obs.room,
+        to_face,
+        obs.turn,
+        &obs.rules,
+        &value,
+    );
+    let can_skip = obs.legal_actions.contains(&Action::Skip);
+
+    match best {
+        Some((action, health, _)) if health > 0 || !can_skip => action,
+        _ if can_skip => Action::Skip,
+        _ => obs.legal_actions.first().copied().unwrap_or(Action::Skip),
+    }
+}
+
+// Returns the first action of the best line with the health and value it
+// ends on. Lines that die are ranked below every line that survives.
+fn best_line(
+    player: &Player,
+    room: &[Card],
+    to_face: usize,
+    turn: u8,
+    rules: &RuleSet,
+    value: &impl Fn(&Player) -> i32,
+) -> Option<(Action, u8, i32)> {
+    let mut best: Option<(Action, u8, i32)> = None;
+
+    for (index, card) in room.iter().enumerate() {
+        for (action, next) in resolutions(player, card, index, turn, rules) {
+            let (health, score) = if next.health == 0 || to_face <= 1 {
+                (next.health, value(&next))
+            } else {
+                let mut rest: Vec<Card> = room.to_vec();
+                rest.remove(index);
+
+                match best_line(&next, &rest, to_face - 1, turn, rules, value) {
+                    Some((_, health, score)) => (health, score),
+                    None => (next.health, value(&next)),
+                }
+            };
+
+            let better = match best {
+                None => true,
+                Some((_, best_health, best_score)) => {
+                    (health > 0, score) > (best_health > 0, best_score)
+                }
+            };
+
+            if better {
+                best = Some((action, health, score));
+            }
+        }
+    }
+
+    return best;
+}
+
+// Every way to resolve the card, with the player it leaves behind.
+fn resolutions(
+    player: &Player,
+    card: &Card,
+    index: usize,
+    turn: u8,
+    rules: &RuleSet,
+) -> Vec<(Action, Player)> {
+    let mut resolutions = Vec::new();
+
+    match card.kind {
+        CardKind::Monster => {
+            let mut next = player.clone();
+            if next.kill(card, rules).is_ok() {
+                resolutions.push((Action::Kill { index }, next));
+            }
+
+            let mut next = player.clone();
+            if next.fight(card).is_ok() {
+                resolutions.push((Action::Fight { index }, next));
+            }
+        }
+        CardKind::Weapon => {
+            let mut next = player.clone();
+            if next.equip_weapon(card).is_ok() {
+                resolutions.push((Action::Equip { index }, next));
+            }
+        }
+        CardKind::Potion => {
+            let mut next = player.clone();
+            if next.heal(card, turn, rules).is_ok() {
+                resolutions.push((Action::Heal { index }, next));
+            }
+        }
+    }
+
+    return resolutions;
+}
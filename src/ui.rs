@@ -1,41 +1,61 @@
 use std::{fmt, io};
 
 use crate::{
-    card::{Card, Rank, Suit},
-    error::{AppError, GameError, UiError},
-    game::{GameInfo, GameOutcome},
+    action::Action,
+    card::{Card, CardKind},
+    deck::Composition,
+    driver::{Command, InputError, InputSource, Renderer},
+    error::UiError,
+    game::{Ending, Game, GameEvent, GameInfo, GameOutcome, Preview},
+    player::WeaponReach,
 };
 
-pub enum Action {
-    Quit,
-    Skip,
-    PrintRules,
-    Kill { index: usize },
-    Heal { index: usize },
-    Fight { index: usize },
-    Equip { index: usize },
-}
-
 pub struct Reader;
 
 impl Reader {
     pub fn read_input() -> Result<String, UiError> {
         let mut action = String::new();
 
-        io::stdin()
+        let read = io::stdin()
             .read_line(&mut action)
             .map_err(|_| UiError::InputReadFailed)?;
 
+        // Nothing read at all, not even a newline, means the end of input.
+        if read == 0 {
+            return Err(UiError::InputClosed);
+        }
+
         return Ok(action.trim().to_string());
     }
 }
 
+// Reads commands from stdin, a leading `?` asks for a preview.
+pub struct TerminalInput;
+
+impl InputSource for TerminalInput {
+    type Error = UiError;
+
+    fn next_command(&mut self, _game: &Game) -> Result<Command, InputError<UiError>> {
+        let input = Reader::read_input().map_err(InputError::Fatal)?;
+
+        if let Some(rest) = input.strip_prefix('?') {
+            return Parser::parse_action(rest)
+                .map(Command::Preview)
+                .map_err(InputError::Invalid);
+        }
+
+        return Parser::parse_action(&input)
+            .map(Command::Play)
+            .map_err(InputError::Invalid);
+    }
+}
+
 pub struct Parser;
 
 impl Parser {
     pub fn parse_action(input: &str) -> Result<Action, UiError> {
         let lower = input.to_lowercase();
-        let mut iter = lower.trim().split_whitespace();
+        let mut iter = lower.split_whitespace();
 
         let command = iter.next().ok_or(UiError::EmptyInput)?;
 
@@ -62,6 +82,10 @@ impl Parser {
 
             "r" => Ok(Action::PrintRules),
 
+            "u" => Ok(Action::Undo),
+
+            "redo" => Ok(Action::Redo),
+
             _ => Err(UiError::UnknownCommand),
         }
     }
@@ -80,16 +104,42 @@ impl Parser {
 }
 
 pub struct Printer {
-    errors: Vec<AppError>,
+    errors: Vec<String>,
+    events: Vec<GameEvent>,
+    preview: Option<Preview>,
 }
 
 impl Printer {
     pub fn new() -> Self {
-        Self { errors: Vec::new() }
+        Self {
+            errors: Vec::new(),
+            events: Vec::new(),
+            preview: None,
+        }
+    }
+
+    pub fn set_preview(&mut self, preview: Preview) {
+        self.preview = Some(preview);
+    }
+
+    pub fn print_preview(&mut self) {
+        if let Some(preview) = self.preview.take() {
+            println!("{}", preview);
+        }
+    }
+
+    pub fn add_error(&mut self, e: &dyn fmt::Display) {
+        self.errors.push(e.to_string());
     }
 
-    pub fn add_error(&mut self, e: AppError) {
-        self.errors.push(e);
+    pub fn add_events(&mut self, events: &[GameEvent]) {
+        self.events.extend_from_slice(events);
+    }
+
+    pub fn print_events(&mut self) {
+        for event in self.events.drain(0..self.events.len()) {
+            println!("{}", &event);
+        }
     }
 
     pub fn print_errors(&mut self) {
@@ -164,51 +214,126 @@ impl Printer {
             String::new(),
         ];
 
+        // Rooms larger than four cards widen the whole table.
+        let card_area_width = CARD_AREA_WIDTH.max(card_lines[0].chars().count());
+        let total_width = TOTAL_WIDTH + card_area_width - CARD_AREA_WIDTH;
+
         // ===== RENDER =====
-        println!("{:-<81}", "");
+        println!("{:-<width$}", "", width = total_width + 3);
         for i in 0..card_lines.len() {
             if i == card_lines.len() - 2 {
-                let left = format!("{:<CARD_AREA_WIDTH$}", card_lines[i]);
+                let left = format!("{:<card_area_width$}", card_lines[i]);
                 let right = format!(
                     "{:>width$}",
                     stats[i],
-                    width = TOTAL_WIDTH - CARD_AREA_WIDTH - 1
+                    width = total_width - card_area_width - 1
                 );
                 println!("|{}{} |", left, right);
             } else {
-                let left = format!("{:<CARD_AREA_WIDTH$}", card_lines[i]);
+                let left = format!("{:<card_area_width$}", card_lines[i]);
                 let right = format!(
                     "{:>width$}",
                     stats[i],
-                    width = TOTAL_WIDTH - CARD_AREA_WIDTH
+                    width = total_width - card_area_width
                 );
                 println!("|{}{} |", left, right);
             }
         }
-        println!("{:-<81}", "");
+        println!("{:-<width$}", "", width = total_width + 3);
+
+        Self::print_counts(&game_info.deck_composition, &game_info.known_bottom);
+    }
+
+    fn print_counts(composition: &Composition, known_bottom: &[Card]) {
+        let counts = |kind: CardKind| {
+            let mut line = format!("{:>4}:", composition.total_strength(kind));
+
+            for (strength, count) in composition.counts(kind) {
+                match count {
+                    1 => line.push_str(&format!(" {}", strength)),
+                    _ => line.push_str(&format!(" {}×{}", strength, count)),
+                }
+            }
+
+            line
+        };
+
+        println!("Left in the dungeon:");
+        println!("  monsters {}", counts(CardKind::Monster));
+        println!("  weapons  {}", counts(CardKind::Weapon));
+        println!("  potions  {}", counts(CardKind::Potion));
+
+        if !known_bottom.is_empty() {
+            let cards: Vec<String> = known_bottom.iter().rev().map(|c| c.to_string()).collect();
+            println!("  known at the bottom, in draw order: {}", cards.join(" "));
+        }
+
+        println!();
+    }
+
+    pub fn print_commands() {
         println!(
             "a = attack with weapon  f = fight barehanded  s = skip  e = equip  h = heal  r = rules"
         );
+        println!("u = undo last move  redo = redo undone move  ? = preview a move");
         println!();
         println!("example commands:");
         println!("s   = skip room");
         println!("e 2 = equip a weapon at position 2");
         println!("a 1 = attack monster at position 1");
+        println!("? a 1 = show what attacking monster at position 1 would do");
+        println!();
+    }
+
+    pub fn print_replay_controls(step: usize, total: usize) {
+        println!("Replay step {} of {}", step, total);
+        println!("n = next step  b = previous step  q = quit");
         println!();
     }
 
     pub fn print_outcome(outcome: GameOutcome) {
-        match outcome {
-            GameOutcome::Win { score } => {
+        let breakdown = outcome.breakdown;
+
+        match outcome.ending {
+            Ending::ClearedDungeon => {
                 println!("You win!");
-                println!("Score: {}", score);
+                println!("You cleared every room of the dungeon.");
             }
-            GameOutcome::Lose { score } => {
+            Ending::LeftLastRoom => {
+                println!("You win!");
+                println!("You left the last room behind.");
+            }
+            Ending::Died => {
                 println!("You lose!");
-                println!("Score: -{}", score);
+                println!("You were slain in the dungeon.");
             }
         }
+
+        println!();
+
+        if outcome.is_win() {
+            println!("Health left:         {:>4}", breakdown.health);
+
+            if breakdown.potion_bonus > 0 {
+                println!("Final potion bonus:  {:>4}", breakdown.potion_bonus);
+            }
+        } else {
+            println!(
+                "Monsters in deck:    {:>4}",
+                -(breakdown.deck_monster_penalty as i16)
+            );
+            println!(
+                "Monsters in room:    {:>4}",
+                -(breakdown.room_monster_penalty as i16)
+            );
+        }
+
+        println!("Score:               {:>4}", outcome.score);
+        if let Some(seed) = outcome.seed {
+            println!("Seed: {seed}");
+        }
     }
+
     pub fn print_rules() {
         let rules_lines = [
             "Scoundrel is a deck based dungeon crawler game.\n",
@@ -225,9 +350,11 @@ impl Printer {
             "The second potion you use is simply discarded. You may not restore your life beyond your starting 20 health.\n",
             "The Game ends when either your life reaches zero or you make your way through the entire Dungeon.\n",
             "Scoring:\n",
-            "If your life has reached zero, your score is the negative sum of all the remaining monsters in the Dungeon.",
+            "If your life has reached zero, your score is the negative sum of all the remaining monsters in the Dungeon and in the Room.",
             "If you have made your way through the entire dungeon, your score is equal to your remaining health points.",
-            "If the deck runs out, and the last cards room is comprised of the remaining 4 or less cards, you don't have to clear that room. You win by default.",
+            "If your life is 20 and the last card you resolved was a Health Potion, its value is added to your score.",
+            "Once the deck runs out, the last room has to be cleared completely, one card at a time. It cannot be avoided.",
+            "With the leave_last_room house rule, you don't have to clear that last room. You win as soon as the deck runs out.\n",
             "Gameplay:\n",
             "On start of each turn, cards from the deck are drawn until there are 4 cards face up. These 4 cards represent the Room.",
             "You may avoid the Room if you wish. If you choose to do so, all four cards in the room will be placed at the bottom of the deck.",
@@ -251,7 +378,7 @@ impl Printer {
             "Legend:\n",
             " ♡ - health",
             " ⚔ - weapon strength",
-            " 🥊 - weapon can fight below",
+            " 🥊 - last monster slain with the weapon",
             " ⏭  - turn when last room skipped",
             " ↺ - turn number",
         ];
@@ -264,80 +391,136 @@ impl Printer {
     }
 }
 
-impl fmt::Display for GameError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let msg = match self {
-            GameError::RoomFull => "The room is already full.",
-            GameError::NotAWeapon => "Selected card is not a weapon.",
-            GameError::NotAPotion => "Selected card is not a potion.",
-            GameError::CannotSkip => "You cannot skip a room you already started playing.",
-            GameError::CannotSkipTwoInRow => "You cannot skip two rooms in a row.",
-            GameError::NotAMonster => "Selected card is not a monster.",
-            GameError::IndexOutOfBounds => "There is no card at the given position.",
-            GameError::NoWeaponEquipped => "You must equip a weapon first.",
-            GameError::MonsterTooStrongForWeapon => "This monster is too strong for your weapon.",
-        };
-
-        write!(f, "{msg}")
+impl Default for Printer {
+    fn default() -> Self {
+        return Self::new();
     }
 }
 
-impl fmt::Display for UiError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let msg = match self {
-            UiError::EmptyInput => "Please enter a command.",
-            UiError::UnknownCommand => "Unknown command.",
-            UiError::MissingIndex => "You must provide a card position.",
-            UiError::InvalidIndex => "There is no card at the given position.",
-            UiError::IndexStartsAtOne => "Card positions start at 1.",
-            UiError::InputReadFailed => "Failed to read input.",
-        };
+impl Renderer for Printer {
+    fn render(&mut self, game: &Game) {
+        Self::clear_screen();
+        Self::print_room(game.game_info());
+        Self::print_commands();
+        self.print_events();
+        self.print_preview();
+        self.print_errors();
+    }
 
-        write!(f, "{msg}")
+    fn show_events(&mut self, events: &[GameEvent]) {
+        self.add_events(events);
     }
-}
 
-impl fmt::Display for AppError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let msg = match self {
-            AppError::Ui(e) => format!("{}", e),
-            AppError::Game(e) => format!("{}", e),
-        };
+    fn show_preview(&mut self, preview: &Preview) {
+        self.set_preview(*preview);
+    }
 
-        write!(f, "{msg}")
+    fn show_error(&mut self, error: &dyn fmt::Display) {
+        self.add_error(error);
     }
-}
 
-impl fmt::Display for Suit {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let msg = match self {
-            Suit::Spades => "♠",
-            Suit::Clubs => "♣",
-            Suit::Diamonds => "♦",
-            Suit::Hearts => "♥",
-        };
+    // Stays up until the player submits anything.
+    fn show_rules(&mut self) {
+        Self::clear_screen();
+        Self::print_rules();
+
+        let _ = Reader::read_input();
+    }
 
-        write!(f, "{msg}")
+    fn show_outcome(&mut self, outcome: &GameOutcome) {
+        Self::print_outcome(outcome.clone());
     }
 }
 
-impl fmt::Display for Rank {
+impl fmt::Display for GameEvent {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let msg = match self {
-            Rank::Jack => "J",
-            Rank::Queen => "Q",
-            Rank::King => "K",
-            Rank::Ace => "A",
-            Rank::Num(v) => &v.to_string(),
-        };
-
-        write!(f, "{msg}")
+        match self {
+            GameEvent::QuitGame => write!(f, "Quitting the game."),
+            GameEvent::RulesPrinted => write!(f, "Rules printed."),
+            GameEvent::Undone => write!(f, "Move undone."),
+            GameEvent::Redone => write!(f, "Move redone."),
+            GameEvent::TurnStarted { turn } => write!(f, "Turn {turn} begins."),
+            GameEvent::TurnEnded => write!(f, "Your turn is over."),
+            GameEvent::RoomSkipped { .. } => write!(f, "You avoided the room."),
+            GameEvent::DamageTaken { amount, source } => {
+                write!(f, "{source} dealt you {amount} damage.")
+            }
+            GameEvent::MonsterSlain {
+                monster,
+                weapon_strength,
+            } => write!(f, "You slew {monster} with your {weapon_strength} weapon."),
+            GameEvent::PotionConsumed { potion, healed } => {
+                write!(f, "{potion} restored {healed} health.")
+            }
+            GameEvent::PotionWasted { potion, limit: 0 } => {
+                write!(
+                    f,
+                    "{potion} was wasted, potions do not work in this dungeon."
+                )
+            }
+            GameEvent::PotionWasted { potion, limit: 1 } => {
+                write!(f, "{potion} was wasted, only one potion works per turn.")
+            }
+            GameEvent::PotionWasted { potion, limit } => {
+                write!(
+                    f,
+                    "{potion} was wasted, only {limit} potions work per turn."
+                )
+            }
+            GameEvent::WeaponEquipped {
+                weapon,
+                replaced: Some(replaced),
+            } => write!(f, "You equipped {weapon} and discarded {replaced}."),
+            GameEvent::WeaponEquipped {
+                weapon,
+                replaced: None,
+            } => write!(f, "You equipped {weapon}."),
+            GameEvent::GameWon => write!(f, "You made it through the dungeon!"),
+            GameEvent::GameLost => write!(f, "You have died."),
+        }
     }
 }
 
-impl fmt::Display for Card {
+impl fmt::Display for Preview {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let msg = format!("{}{}", self.suit, self.rank);
-        write!(f, "{msg}")
+        let mut parts = Vec::new();
+
+        match self.health_change {
+            0 => parts.push(format!("your health stays at {}", self.health_after)),
+            change if change < 0 => parts.push(format!(
+                "you'll take {} damage, leaving you at {}",
+                -change, self.health_after
+            )),
+            change => parts.push(format!(
+                "you'll heal {}, up to {}",
+                change, self.health_after
+            )),
+        }
+
+        if self.potion_wasted {
+            parts.push("the potion will be wasted".to_string());
+        }
+
+        if self.reach_after != self.reach_before {
+            match self.reach_after {
+                WeaponReach::Unarmed => parts.push("you'll have no weapon".to_string()),
+                WeaponReach::Any => parts.push("your weapon will hit any monster".to_string()),
+                WeaponReach::UpTo(strongest) if strongest < 2 => {
+                    parts.push("your weapon won't hit any monster after that".to_string())
+                }
+                WeaponReach::UpTo(strongest) => parts.push(format!(
+                    "your weapon will then only hit monsters below {}",
+                    strongest + 1
+                )),
+            }
+        }
+
+        if self.health_after == 0 {
+            parts.push("this kills you".to_string());
+        } else if self.ends_turn {
+            parts.push("this ends the turn".to_string());
+        }
+
+        write!(f, "Preview: {}.", parts.join(", "))
     }
 }
@@ -0,0 +1,34 @@
+use alloc::vec::Vec;
+
+use crate::{
+    action::Action, card::Card, deck::Composition, discard::DiscardedCard, player::Player,
+    rules::RuleSet,
+};
+
+// Everything the player can legitimately know. Fair bots must only ever be
+// handed this view.
+#[derive(Debug, Clone)]
+pub struct Observation {
+    pub rules: RuleSet,
+    pub turn: u8,
+    pub last_skipped_turn: u8,
+    pub player: Player,
+    pub room: Vec<Card>,
+    pub deck_size: usize,
+    pub deck_composition: Composition,
+    // Cards from skipped rooms still at the bottom of the deck, bottom card first.
+    pub known_bottom: Vec<Card>,
+    pub discard: Vec<DiscardedCard>,
+    // Decides the final potion bonus if the game ends right away.
+    pub last_resolved: Option<Card>,
+    pub legal_actions: Vec<Action>,
+}
+
+// The privileged view for solvers and debugging, it reveals the deck order.
+#[derive(Debug, Clone)]
+pub struct FullState {
+    pub observation: Observation,
+    // The remaining cards in the order they will be drawn.
+    pub deck: Vec<Card>,
+    pub seed: Option<u64>,
+}